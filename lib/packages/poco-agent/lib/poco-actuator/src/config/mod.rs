@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use poco_types::types::round::{BlockTimestamp, RoundDuration};
 use poco_types::types::task::id::TaskId;
 use poco_types::types::task::{
     TaskConfig, TaskInputSource, TaskOffer, TaskOutputSource, TaskRequirement, OnChainTaskConfig
@@ -45,6 +46,14 @@ pub struct RawTaskConfigFile {
     pub offer: Vec<TaskOffer>,
     pub config: serde_json::Value,
     pub r#type: String,
+    #[serde(default)]
+    pub claim_deadline: Option<BlockTimestamp>,
+    #[serde(default)]
+    pub execution_timeout: Option<RoundDuration>,
+    #[serde(default)]
+    pub replicas: Option<u32>,
+    #[serde(default)]
+    pub quorum: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -107,6 +116,10 @@ impl RawTaskConfigFile {
             offer: self.offer.clone(),
             config,
             r#type,
+            claim_deadline: self.claim_deadline,
+            execution_timeout: self.execution_timeout,
+            replicas: self.replicas.unwrap_or(1),
+            quorum: self.quorum.unwrap_or(1),
         })
     }
 }
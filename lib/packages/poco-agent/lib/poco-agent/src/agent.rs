@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fmt::{Debug, Display};
 use std::str::FromStr;
 use std::sync::Arc;
@@ -13,10 +14,17 @@ use near_primitives::transaction::FunctionCallAction;
 use near_primitives::transaction::{Action, Transaction};
 use near_primitives::types::{AccountId, Balance, BlockReference, Finality, Gas};
 use near_primitives::views::{AccessKeyView, AccountView, FinalExecutionStatus, QueryRequest};
-use poco_types::types::event::IndexedEvent;
-use poco_types::types::round::{RoundId, RoundInfo, RoundStatus};
+use poco_types::types::config::ContractConfig;
+use poco_types::types::dispute::Dispute;
+use poco_types::types::error::PocoError;
+use poco_types::types::event::{EventFilter, EventQueryResult};
+use poco_types::types::round::{BlockTimestamp, RoundId, RoundInfo, RoundStatus, RoundSummary};
 use poco_types::types::task::id::TaskId;
-use poco_types::types::task::{TaskConfig, OnChainTaskConfig};
+use poco_types::types::task::status::{TaskAssignment, TaskResult, TaskStatus};
+use poco_types::types::task::task_type::TaskType;
+use poco_types::types::task::{TaskConfig, OnChainTaskConfig, TaskOffer, TaskRequirement};
+use poco_types::types::uint::U256;
+use poco_types::types::user::{EndpointProtocol, Reputation};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::json;
@@ -27,6 +35,24 @@ use crate::agent::PocoAgentError::{
 };
 use crate::config::PocoAgentConfig;
 
+/// Gas attached to publish a single task. Publishing validates the config and draws the
+/// workers and verifiers among the contract's candidate pool, which is capped at
+/// `MAX_ASSIGNMENT_CANDIDATES` stakers, so the cost does not grow with the number of
+/// stakers. The budget is conservative rather than measured, the unused part is refunded.
+pub const PUBLISH_TASK_GAS: Gas = 30_000_000_000_000;
+
+/// Number of tasks sent in each `publish_tasks` transaction. Each task gets the budget of
+/// `PUBLISH_TASK_GAS`, so a batch attaches the 300 Tgas a transaction may use at most.
+pub const PUBLISH_BATCH_SIZE: usize = 10;
+
+/// Number of tasks each `finalize_round` call goes through. A call that runs out of gas
+/// changes nothing, so it can be retried with a smaller batch.
+pub const FINALIZE_BATCH_SIZE: u32 = 50;
+
+/// Deposit attached to profile updates to pay for the storage they use, enough for 1 kB at
+/// the storage price of 1e19 yoctoNEAR per byte. The contract refunds what is left.
+pub const PROFILE_STORAGE_DEPOSIT: Balance = 10_000_000_000_000_000_000_000;
+
 pub struct PocoAgent {
     config: Arc<PocoAgentConfig>,
     inner: JsonRpcClient,
@@ -84,8 +110,12 @@ pub enum PocoAgentError {
     UnexpectedTxExecutionStatus(TxExecutionStatus),
     #[error("Transaction execution error: {0}")]
     TxExecutionError(#[from] near_primitives::errors::TxExecutionError),
+    #[error("Contract error: {0}")]
+    ContractError(PocoError),
     #[error("Failed to parse json: {0}")]
     JsonError(#[from] serde_json::Error),
+    #[error("Required escrow does not fit in a balance")]
+    EscrowOverflow,
 }
 
 impl<T: Debug + Display + Send + Sync + 'static> From<near_jsonrpc_client::errors::JsonRpcError<T>>
@@ -96,6 +126,16 @@ impl<T: Debug + Display + Send + Sync + 'static> From<near_jsonrpc_client::error
     }
 }
 
+impl PocoAgentError {
+    /// Replaces errors carrying an encoded `PocoError` from the contract with `ContractError`.
+    fn decode_contract_error(self) -> Self {
+        match PocoError::decode(&self.to_string()) {
+            Some(error) => PocoAgentError::ContractError(error),
+            None => self,
+        }
+    }
+}
+
 impl PocoAgent {
     pub fn build(config: Arc<PocoAgentConfig>) -> Result<Self, PocoAgentBuildError> {
         let mut headers = reqwest::header::HeaderMap::with_capacity(1);
@@ -233,7 +273,11 @@ impl PocoAgent {
             },
         };
 
-        let response = self.inner.call(request).await?;
+        let response = self
+            .inner
+            .call(request)
+            .await
+            .map_err(|e| PocoAgentError::from(e).decode_contract_error())?;
 
         Self::get_buffer_from_call_response(response)
     }
@@ -292,7 +336,9 @@ impl PocoAgent {
 
         match response.status {
             FinalExecutionStatus::SuccessValue(buffer) => Ok((gas_burnt, buffer)),
-            FinalExecutionStatus::Failure(error) => Err(error)?,
+            FinalExecutionStatus::Failure(error) => {
+                Err(PocoAgentError::from(error).decode_contract_error())
+            }
             _ => Err(UnexpectedTxExecutionStatus(response.status.into())),
         }
     }
@@ -361,6 +407,14 @@ impl PocoAgent {
         Ok(gas)
     }
 
+    pub async fn get_contract_config(&self) -> Result<ContractConfig, PocoAgentError> {
+        self.call_view_function_json("get_config", &json!({})).await
+    }
+
+    pub async fn get_contract_version(&self) -> Result<String, PocoAgentError> {
+        self.call_view_function_json("version", &json!({})).await
+    }
+
     pub async fn get_round_status(&self) -> Result<RoundStatus, PocoAgentError> {
         let response = self
             .call_view_function_json("get_round_status", &json!({}))
@@ -377,6 +431,29 @@ impl PocoAgent {
         Ok(response)
     }
 
+    pub async fn get_round_info_by_id(
+        &self,
+        round_id: RoundId,
+    ) -> Result<Option<RoundInfo>, PocoAgentError> {
+        self.call_view_function_json("get_round_info_by_id", &json!({ "round_id": round_id }))
+            .await
+    }
+
+    pub async fn list_rounds(
+        &self,
+        from: RoundId,
+        limit: u32,
+    ) -> Result<Vec<RoundInfo>, PocoAgentError> {
+        self.call_view_function_json(
+            "list_rounds",
+            &json!({
+                "from": from,
+                "limit": limit
+            }),
+        )
+        .await
+    }
+
     pub async fn count_events(&self) -> Result<u32, PocoAgentError> {
         let response = self
             .call_view_function_json("count_events", &json!({}))
@@ -397,7 +474,7 @@ impl PocoAgent {
         &self,
         from: u32,
         count: u32,
-    ) -> Result<Vec<IndexedEvent>, PocoAgentError> {
+    ) -> Result<EventQueryResult, PocoAgentError> {
         let response = self
             .call_view_function_json(
                 "query_events",
@@ -411,6 +488,40 @@ impl PocoAgent {
         Ok(response)
     }
 
+    pub async fn query_filtered_events(
+        &self,
+        filter: EventFilter,
+        from: u32,
+        count: u32,
+    ) -> Result<EventQueryResult, PocoAgentError> {
+        self.call_view_function_json(
+            "query_filtered_events",
+            &json!({
+                "filter": filter,
+                "from": from,
+                "count": count
+            }),
+        )
+        .await
+    }
+
+    pub async fn query_round_events(
+        &self,
+        round_id: RoundId,
+        from: u32,
+        count: u32,
+    ) -> Result<EventQueryResult, PocoAgentError> {
+        self.call_view_function_json(
+            "query_round_events",
+            &json!({
+                "round_id": round_id,
+                "from": from,
+                "count": count
+            }),
+        )
+        .await
+    }
+
     pub async fn get_user_endpoint(
         &self,
         account_id: Option<AccountId>,
@@ -424,6 +535,69 @@ impl PocoAgent {
             "set_user_endpoint",
             &json!({ "endpoint": endpoint }),
             10_000_000_000_000,
+            PROFILE_STORAGE_DEPOSIT,
+        )
+        .await
+    }
+
+    /// Endpoints of the account by protocol.
+    pub async fn get_user_endpoints(
+        &self,
+        account_id: Option<AccountId>,
+    ) -> Result<BTreeMap<EndpointProtocol, String>, PocoAgentError> {
+        self.call_view_function_json("get_user_endpoints", &json!({ "account_id": account_id }))
+            .await
+    }
+
+    pub async fn set_user_endpoints(
+        &self,
+        endpoints: &BTreeMap<EndpointProtocol, String>,
+    ) -> Result<Gas, PocoAgentError> {
+        self.call_change_function_json_no_response(
+            "set_user_endpoints",
+            &json!({ "endpoints": endpoints }),
+            10_000_000_000_000,
+            PROFILE_STORAGE_DEPOSIT,
+        )
+        .await
+    }
+
+    pub async fn list_users(&self, from: u32, limit: u32) -> Result<Vec<AccountId>, PocoAgentError> {
+        self.call_view_function_json("list_users", &json!({ "from": from, "limit": limit }))
+            .await
+    }
+
+    /// Accounts among the `limit` directory entries from `from` whose properties satisfy
+    /// all of the requirements.
+    pub async fn find_users_matching(
+        &self,
+        requirements: &[TaskRequirement],
+        from: u32,
+        limit: u32,
+    ) -> Result<Vec<AccountId>, PocoAgentError> {
+        self.call_view_function_json(
+            "find_users_matching",
+            &json!({ "requirements": requirements, "from": from, "limit": limit }),
+        )
+        .await
+    }
+
+    /// Declares capability properties of the signer, such as `cpu_cores`.
+    pub async fn set_user_props(&self, props: &[(String, U256)]) -> Result<Gas, PocoAgentError> {
+        self.call_change_function_json_no_response(
+            "set_user_props",
+            &json!({ "props": props }),
+            10_000_000_000_000,
+            PROFILE_STORAGE_DEPOSIT,
+        )
+        .await
+    }
+
+    pub async fn remove_user_prop(&self, name: &str) -> Result<Gas, PocoAgentError> {
+        self.call_change_function_json_no_response(
+            "remove_user_prop",
+            &json!({ "name": name }),
+            10_000_000_000_000,
             0,
         )
         .await
@@ -434,6 +608,47 @@ impl PocoAgent {
             .await
     }
 
+    /// Finalizes up to `limit` more tasks of the round, see `FINALIZE_BATCH_SIZE`.
+    /// The summary is only returned by the call that finalizes the last task.
+    pub async fn finalize_round(
+        &self,
+        round_id: RoundId,
+        limit: u32,
+    ) -> Result<(Gas, Option<RoundSummary>), PocoAgentError> {
+        self.call_change_function_json(
+            "finalize_round",
+            &json!({ "round_id": round_id, "limit": limit }),
+            300_000_000_000_000,
+            0,
+        )
+        .await
+    }
+
+    pub async fn get_round_summary(
+        &self,
+        round_id: RoundId,
+    ) -> Result<Option<RoundSummary>, PocoAgentError> {
+        self.call_view_function_json("get_round_summary", &json!({ "round_id": round_id }))
+            .await
+    }
+
+    pub async fn get_task_type(&self, name: &str) -> Result<Option<TaskType>, PocoAgentError> {
+        self.call_view_function_json("get_task_type", &json!({ "name": name }))
+            .await
+    }
+
+    pub async fn list_task_types(
+        &self,
+        from: u32,
+        limit: u32,
+    ) -> Result<Vec<TaskType>, PocoAgentError> {
+        self.call_view_function_json(
+            "list_task_types",
+            &json!({ "from": from, "limit": limit }),
+        )
+        .await
+    }
+
     pub async fn publish_task(
         &self,
         task_config: TaskConfig,
@@ -443,6 +658,9 @@ impl PocoAgent {
             config: TaskConfig,
         }
 
+        // The contract escrows the largest bounty of every replica, so it has to be attached as deposit.
+        let deposit = required_escrow(&task_config)?;
+
         let task_config = WrappedTaskConfig {
             config: task_config,
         };
@@ -451,18 +669,56 @@ impl PocoAgent {
             .call_change_function_json::<WrappedTaskConfig, TaskId>(
                 "publish_task",
                 &task_config,
-                10_000_000_000_000,
-                0,
+                PUBLISH_TASK_GAS,
+                deposit,
             )
             .await?;
 
         Ok(result)
     }
 
+    /// Publishes the tasks in batches of `PUBLISH_BATCH_SIZE`, one transaction per batch,
+    /// attaching the sum of the batch's required escrows. Batches are sent in order and
+    /// stop at the first failure, the batches sent before it stay published.
+    pub async fn publish_tasks(
+        &self,
+        task_configs: Vec<TaskConfig>,
+    ) -> Result<(Gas, Vec<TaskId>), PocoAgentError> {
+        #[derive(Serialize)]
+        struct WrappedTaskConfigs<'a> {
+            configs: &'a [TaskConfig],
+        }
+
+        let mut gas = 0;
+        let mut task_ids = Vec::with_capacity(task_configs.len());
+
+        for configs in task_configs.chunks(PUBLISH_BATCH_SIZE) {
+            let deposit = configs.iter().try_fold(0 as Balance, |deposit, config| {
+                deposit
+                    .checked_add(required_escrow(config)?)
+                    .ok_or(PocoAgentError::EscrowOverflow)
+            })?;
+
+            let (batch_gas, batch_task_ids) = self
+                .call_change_function_json::<WrappedTaskConfigs, Vec<TaskId>>(
+                    "publish_tasks",
+                    &WrappedTaskConfigs { configs },
+                    PUBLISH_TASK_GAS * configs.len() as Gas,
+                    deposit,
+                )
+                .await?;
+
+            gas += batch_gas;
+            task_ids.extend(batch_task_ids);
+        }
+
+        Ok((gas, task_ids))
+    }
+
     pub async fn query_specific_task(
         &self,
         task_id: TaskId
-    ) -> Result<(OnChainTaskConfig), PocoAgentError> {
+    ) -> Result<Option<OnChainTaskConfig>, PocoAgentError> {
         let round_id = task_id.get_round_id();
         let task_nounce = task_id.get_task_nonce();
 
@@ -471,4 +727,201 @@ impl PocoAgent {
 
         Ok(response)
     }
+
+    pub async fn list_tasks(
+        &self,
+        round_id: RoundId,
+        from: u32,
+        limit: u32,
+    ) -> Result<Vec<OnChainTaskConfig>, PocoAgentError> {
+        self.call_view_function_json(
+            "list_tasks",
+            &json!({ "round_id": round_id, "from": from, "limit": limit }),
+        )
+        .await
+    }
+
+    pub async fn list_tasks_by_owner(
+        &self,
+        account: &AccountId,
+        from: u32,
+        limit: u32,
+    ) -> Result<Vec<OnChainTaskConfig>, PocoAgentError> {
+        self.call_view_function_json(
+            "list_tasks_by_owner",
+            &json!({ "account": account, "from": from, "limit": limit }),
+        )
+        .await
+    }
+
+    pub async fn list_tasks_by_worker(
+        &self,
+        account: &AccountId,
+        from: u32,
+        limit: u32,
+    ) -> Result<Vec<OnChainTaskConfig>, PocoAgentError> {
+        self.call_view_function_json(
+            "list_tasks_by_worker",
+            &json!({ "account": account, "from": from, "limit": limit }),
+        )
+        .await
+    }
+
+    /// Workers and verifiers drawn for the task, if the contract assigned any.
+    pub async fn get_assignments(
+        &self,
+        task_id: TaskId,
+    ) -> Result<Option<TaskAssignment>, PocoAgentError> {
+        self.call_view_function_json("get_assignments", &json!({ "task_id": task_id }))
+            .await
+    }
+
+    pub async fn claim_task(&self, task_id: TaskId) -> Result<Gas, PocoAgentError> {
+        self.call_change_function_json_no_response(
+            "claim_task",
+            &json!({ "task_id": task_id }),
+            10_000_000_000_000,
+            0,
+        )
+        .await
+    }
+
+    pub async fn cancel_task(&self, task_id: TaskId) -> Result<Gas, PocoAgentError> {
+        self.call_change_function_json_no_response(
+            "cancel_task",
+            &json!({ "task_id": task_id }),
+            10_000_000_000_000,
+            0,
+        )
+        .await
+    }
+
+    /// Replaces the offers of an unclaimed task, attaching `deposit` to top up its escrow.
+    pub async fn update_task_offer(
+        &self,
+        task_id: TaskId,
+        offers: Vec<TaskOffer>,
+        deposit: Balance,
+    ) -> Result<Gas, PocoAgentError> {
+        self.call_change_function_json_no_response(
+            "update_task_offer",
+            &json!({ "task_id": task_id, "offers": offers }),
+            10_000_000_000_000,
+            deposit,
+        )
+        .await
+    }
+
+    pub async fn submit_task_result(
+        &self,
+        task_id: TaskId,
+        output: &str,
+        digest: &str,
+    ) -> Result<Gas, PocoAgentError> {
+        self.call_change_function_json_no_response(
+            "submit_task_result",
+            &json!({ "task_id": task_id, "output": output, "digest": digest }),
+            10_000_000_000_000,
+            0,
+        )
+        .await
+    }
+
+    pub async fn get_task_results(&self, task_id: TaskId) -> Result<Vec<TaskResult>, PocoAgentError> {
+        self.call_view_function_json("get_task_results", &json!({ "task_id": task_id }))
+            .await
+    }
+
+    /// Disputes the accepted result of a task, attaching `bond` as the challenger's bond.
+    pub async fn dispute_result(
+        &self,
+        task_id: TaskId,
+        counter_digest: &str,
+        bond: Balance,
+    ) -> Result<Gas, PocoAgentError> {
+        self.call_change_function_json_no_response(
+            "dispute_result",
+            &json!({ "task_id": task_id, "counter_digest": counter_digest }),
+            10_000_000_000_000,
+            bond,
+        )
+        .await
+    }
+
+    pub async fn vote_on_dispute(&self, task_id: TaskId, digest: &str) -> Result<Gas, PocoAgentError> {
+        self.call_change_function_json_no_response(
+            "vote_on_dispute",
+            &json!({ "task_id": task_id, "digest": digest }),
+            300_000_000_000_000,
+            0,
+        )
+        .await
+    }
+
+    /// Closes a dispute left unresolved past its deadline, refunding the challenger's bond.
+    pub async fn expire_dispute(&self, task_id: TaskId) -> Result<Gas, PocoAgentError> {
+        self.call_change_function_json_no_response(
+            "expire_dispute",
+            &json!({ "task_id": task_id }),
+            10_000_000_000_000,
+            0,
+        )
+        .await
+    }
+
+    pub async fn get_dispute_deadline(
+        &self,
+        task_id: TaskId,
+    ) -> Result<Option<BlockTimestamp>, PocoAgentError> {
+        self.call_view_function_json("get_dispute_deadline", &json!({ "task_id": task_id }))
+            .await
+    }
+
+    pub async fn get_dispute(&self, task_id: TaskId) -> Result<Option<Dispute>, PocoAgentError> {
+        self.call_view_function_json("get_dispute", &json!({ "task_id": task_id }))
+            .await
+    }
+
+    pub async fn get_reputation(&self, account_id: &AccountId) -> Result<Reputation, PocoAgentError> {
+        self.call_view_function_json("get_reputation", &json!({ "account_id": account_id }))
+            .await
+    }
+
+    pub async fn stake(&self, amount: Balance) -> Result<(Gas, U256), PocoAgentError> {
+        self.call_change_function_json("stake", &json!({}), 10_000_000_000_000, amount)
+            .await
+    }
+
+    pub async fn unstake(&self, amount: U256) -> Result<(Gas, U256), PocoAgentError> {
+        self.call_change_function_json(
+            "unstake",
+            &json!({ "amount": amount }),
+            10_000_000_000_000,
+            0,
+        )
+        .await
+    }
+
+    pub async fn withdraw_unstaked(&self) -> Result<(Gas, U256), PocoAgentError> {
+        self.call_change_function_json("withdraw_unstaked", &json!({}), 10_000_000_000_000, 0)
+            .await
+    }
+
+    pub async fn get_task_status(
+        &self,
+        task_id: TaskId,
+    ) -> Result<Option<TaskStatus>, PocoAgentError> {
+        self.call_view_function_json("get_task_status", &json!({ "task_id": task_id }))
+            .await
+    }
+}
+
+fn required_escrow(task_config: &TaskConfig) -> Result<Balance, PocoAgentError> {
+    let escrow = task_config.required_escrow();
+
+    if escrow > U256::from(Balance::MAX) {
+        return Err(PocoAgentError::EscrowOverflow);
+    }
+
+    Ok(escrow.as_u128())
 }
@@ -4,6 +4,7 @@ use clap::{Arg, Command};
 use strum::Display;
 
 use poco_agent::types::AccountId;
+use poco_types::types::user::EndpointProtocol;
 
 #[derive(Debug, Display)]
 pub enum BackendCommand {
@@ -31,9 +32,19 @@ pub enum BackendCommand {
     },
     // PoCo Contract Commands
     RoundStatusCommand,
-    RoundInfoCommand,
+    RoundInfoCommand {
+        round_id: Option<u32>,
+    },
+    ListRoundsCommand {
+        from: u32,
+        limit: u32,
+    },
     CountEventsCommand,
     CountTasksCommand,
+    ListTaskTypesCommand {
+        from: u32,
+        limit: u32,
+    },
     QuerySpecificTaskCommand {
         task_id: u64,
     },
@@ -47,15 +58,45 @@ pub enum BackendCommand {
     },
     SetUserEndpointCommand {
         endpoint: String,
+        protocol: EndpointProtocol,
+    },
+    SetUserPropCommand {
+        name: String,
+        value: u128,
+    },
+    RemoveUserPropCommand {
+        name: String,
     },
     StartRoundCommand,
+    FinalizeRoundCommand {
+        round_id: u32,
+    },
     // Task Related Commands
     PublishTaskCommand {
         task_config_path: String,
     },
     ExecuteTaskCommand {
         task_id: u64,
-    }
+    },
+    ClaimTaskCommand {
+        task_id: u64,
+    },
+    CancelTaskCommand {
+        task_id: u64,
+    },
+    SubmitTaskResultCommand {
+        task_id: u64,
+        output: String,
+        digest: String,
+    },
+    // Stake Commands
+    StakeCommand {
+        amount: u128,
+    },
+    UnstakeCommand {
+        amount: u128,
+    },
+    WithdrawUnstakedCommand,
 }
 
 #[derive(Debug, Clone)]
@@ -102,9 +143,19 @@ pub fn get_command_instance(in_ui_mode: bool) -> Command {
             .about("View account")
             .arg(Arg::new("account-id").required(true).index(1)),
         subcommand("round-status").about("Get round status"),
-        subcommand("round-info").about("Get round info"),
+        subcommand("round-info")
+            .about("Get round info")
+            .arg(Arg::new("round-id").required(false).index(1)),
+        subcommand("list-rounds")
+            .about("List rounds")
+            .arg(Arg::new("from").required(true).index(1))
+            .arg(Arg::new("limit").required(true).index(2)),
         subcommand("count-events").about("Count events"),
         subcommand("count-tasks").about("Count tasks"),
+        subcommand("list-task-types")
+            .about("List registered task types")
+            .arg(Arg::new("from").required(false).index(1).default_value("0"))
+            .arg(Arg::new("limit").required(false).index(2).default_value("10")),
         subcommand("query-events")
             .about("Query events")
             .arg(
@@ -121,11 +172,19 @@ pub fn get_command_instance(in_ui_mode: bool) -> Command {
                     .default_value("10"),
             ),
         subcommand("get-user-endpoint")
-            .about("Get User Endpoint")
+            .about("Get User Endpoints")
             .arg(Arg::new("account-id").required(false).index(1)),
         subcommand("set-user-endpoint")
-            .about("Set User Endpoint")
-            .arg(Arg::new("endpoint").required(true).index(1)),
+            .about("Set User Endpoint of a protocol: grpc, http or ipfs")
+            .arg(Arg::new("endpoint").required(true).index(1))
+            .arg(Arg::new("protocol").required(false).index(2).default_value("http")),
+        subcommand("set-user-prop")
+            .about("Declare a capability property, e.g. cpu_cores")
+            .arg(Arg::new("name").required(true).index(1))
+            .arg(Arg::new("value").required(true).index(2)),
+        subcommand("remove-user-prop")
+            .about("Remove a declared property")
+            .arg(Arg::new("name").required(true).index(1)),
         subcommand("ipfs")
             .about("IPFS")
             .subcommand_required(true)
@@ -145,15 +204,36 @@ pub fn get_command_instance(in_ui_mode: bool) -> Command {
                     .arg(Arg::new("hash").required(false).index(1)),
             ]),
         subcommand("start-round").about("Start new round"),
+        subcommand("finalize-round")
+            .about("Finalize ended round")
+            .arg(Arg::new("round-id").required(true).index(1)),
         subcommand("query-specific-task")
             .about("Query specific task")
             .arg(Arg::new("task-id").required(true).index(1)),
         subcommand("publish-task")
-            .about("Publish task")
+            .about("Publish tasks from a task config file, manifest or directory")
             .arg(Arg::new("task-config-path").required(true).index(1)),
         subcommand("execute-task")
             .about("Execute task")
             .arg(Arg::new("task-id").required(true).index(1)),
+        subcommand("claim-task")
+            .about("Claim task")
+            .arg(Arg::new("task-id").required(true).index(1)),
+        subcommand("cancel-task")
+            .about("Cancel unclaimed task")
+            .arg(Arg::new("task-id").required(true).index(1)),
+        subcommand("submit-result")
+            .about("Submit task result")
+            .arg(Arg::new("task-id").required(true).index(1))
+            .arg(Arg::new("output").required(true).index(2))
+            .arg(Arg::new("digest").required(true).index(3)),
+        subcommand("stake")
+            .about("Stake yoctoNEAR")
+            .arg(Arg::new("amount").required(true).index(1)),
+        subcommand("unstake")
+            .about("Unstake yoctoNEAR")
+            .arg(Arg::new("amount").required(true).index(1)),
+        subcommand("withdraw-unstaked").about("Withdraw unbonded stake"),
     ];
 
     let command = if in_ui_mode {
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use poco_types::types::round::RoundId;
 use poco_types::types::task::id::TaskId;
+use poco_types::types::task::status::{TaskResult, TaskStatus};
 use poco_types::types::task::OnChainTaskConfig;
 use super::super::super::util::convert_account_id_from_sdk_to_primitives;
 // use poco_types::types::convert_account_id_from_sdk_to_primitives;
@@ -36,6 +37,18 @@ pub trait ContractEventHandler {
         field: &str,
         value: &str,
     ) -> Result<Self::Output, Self::Error>;
+
+    async fn handle_task_status_update_event(
+        &self,
+        task_id: &TaskId,
+        status: &TaskStatus,
+    ) -> Result<Self::Output, Self::Error>;
+
+    async fn handle_task_result_submitted_event(
+        &self,
+        task_id: &TaskId,
+        result: &TaskResult,
+    ) -> Result<Self::Output, Self::Error>;
 }
 
 #[derive(Debug)]
@@ -67,6 +80,17 @@ impl ContractEventHandler for Backend {
 
                     backend.handle_user_profile_field_update_event(&user_id, field, value).await
                 }
+                ContractEventPayload::TaskStatusUpdateEvent { task_id, status } => {
+                    backend.handle_task_status_update_event(task_id, status).await
+                }
+                ContractEventPayload::TaskResultSubmittedEvent { task_id, result } => {
+                    backend.handle_task_result_submitted_event(task_id, result).await
+                }
+                payload => {
+                    log_string(&backend.ui_sender, format!("Event: {:?}", payload));
+
+                    Ok(())
+                }
             };
 
             // if let Err(e) = ret {
@@ -95,9 +119,13 @@ impl ContractEventHandler for Backend {
             .unwrap();
 
         match self.config.app.task_policy {
-            PocoTaskPolicy::AlwaysTaken => {
-                // let _ = agent.take_task(task_id).await;
-            }
+            PocoTaskPolicy::AlwaysTaken => match self.agent.claim_task(task_id.clone()).await {
+                Ok(_) => log_string(&self.ui_sender, format!("Task claimed: {:?}", task_id)),
+                Err(error) => log_string(
+                    &self.ui_sender,
+                    format!("Failed to claim task {:?}: {error}", task_id),
+                ),
+            },
             PocoTaskPolicy::AlwaysIgnore => {}
         }
 
@@ -117,4 +145,33 @@ impl ContractEventHandler for Backend {
 
         Ok(())
     }
-}
+
+    async fn handle_task_status_update_event(
+        &self,
+        task_id: &TaskId,
+        status: &TaskStatus,
+    ) -> Result<Self::Output, Self::Error> {
+        log_string(
+            &self.ui_sender,
+            format!("Task {:?} status changed to {status}", task_id),
+        );
+
+        Ok(())
+    }
+
+    async fn handle_task_result_submitted_event(
+        &self,
+        task_id: &TaskId,
+        result: &TaskResult,
+    ) -> Result<Self::Output, Self::Error> {
+        log_string(
+            &self.ui_sender,
+            format!(
+                "Task {:?} result submitted by {}: {}",
+                task_id, result.worker, result.output
+            ),
+        );
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -1,4 +1,5 @@
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::sync::Arc;
 use async_trait::async_trait;
@@ -6,11 +7,16 @@ use futures::Future;
 use tokio::sync::mpsc;
 
 // use anyhow::Ok;
+use poco_types::types::event::EventQueryResult;
 use poco_types::types::round::RoundStatus;
+use poco_types::types::task::TaskConfig;
+use poco_types::types::uint::U256;
+use poco_types::types::user::EndpointProtocol;
 
 use poco_actuator::config::{RawTaskConfigFile, RawTaskInputSource, ConvertRPCConfig};
-use poco_actuator::get_actuator;
+use poco_actuator::{get_actuator, BoxedTaskActuator};
 use poco_actuator::rpc::client;
+use poco_agent::agent::FINALIZE_BATCH_SIZE;
 use poco_agent::types::AccountId;
 use poco_ipfs::client::GetFileProgress;
 use tui::backend;
@@ -21,7 +27,10 @@ use crate::app::backend::command::BackendCommand::{
     CountEventsCommand, CountTasksCommand, GasPriceCommand, GetUserEndpointCommand, HelpCommand, IpfsAddFileCommand,
     IpfsCatFileCommand, IpfsFileStatusCommand, IpfsGetFileCommand, NetworkStatusCommand,
     PublishTaskCommand, QueryEventsCommand, RoundInfoCommand, RoundStatusCommand,
-    SetUserEndpointCommand, StartRoundCommand, StatusCommand, ViewAccountCommand,QuerySpecificTaskCommand, ExecuteTaskCommand
+    SetUserEndpointCommand, StartRoundCommand, StatusCommand, ViewAccountCommand,QuerySpecificTaskCommand, ExecuteTaskCommand,
+    ClaimTaskCommand, SubmitTaskResultCommand, StakeCommand, UnstakeCommand,
+    WithdrawUnstakedCommand, FinalizeRoundCommand, ListRoundsCommand, CancelTaskCommand,
+    ListTaskTypesCommand, SetUserPropCommand, RemoveUserPropCommand
 };
 use crate::app::ui::event::{CommandExecutionStage, CommandExecutionStatus};
 use crate::app::ui::event::UIActionSender;
@@ -39,9 +48,15 @@ pub trait CommandExecutor {
                 self.execute_view_account_command(command_source, account_id)
             }
             RoundStatusCommand => self.execute_round_status_command(command_source),
-            RoundInfoCommand => self.execute_round_info_command(command_source),
+            RoundInfoCommand { round_id } => self.execute_round_info_command(command_source, round_id),
+            ListRoundsCommand { from, limit } => {
+                self.execute_list_rounds_command(command_source, from, limit)
+            }
             CountEventsCommand => self.execute_count_events_command(command_source),
             CountTasksCommand => self.execute_count_tasks_command(command_source),
+            ListTaskTypesCommand { from, limit } => {
+                self.execute_list_task_types_command(command_source, from, limit)
+            }
             QueryEventsCommand { from, count } => {
                 self.execute_query_events_command(command_source, from, count)
             }
@@ -51,8 +66,14 @@ pub trait CommandExecutor {
             GetUserEndpointCommand { account_id } => {
                 self.execute_get_user_endpoint_command(command_source, account_id)
             }
-            SetUserEndpointCommand { endpoint } => {
-                self.execute_set_user_endpoint_command(command_source, endpoint)
+            SetUserEndpointCommand { endpoint, protocol } => {
+                self.execute_set_user_endpoint_command(command_source, endpoint, protocol)
+            }
+            SetUserPropCommand { name, value } => {
+                self.execute_set_user_prop_command(command_source, name, value)
+            }
+            RemoveUserPropCommand { name } => {
+                self.execute_remove_user_prop_command(command_source, name)
             }
             IpfsAddFileCommand { file_path } => {
                 self.execute_ipfs_add_file_command(command_source, file_path)
@@ -68,12 +89,25 @@ pub trait CommandExecutor {
                 self.execute_ipfs_file_status_command(command_source, file_hash)
             }
             StartRoundCommand => self.execute_start_round_command(command_source),
+            FinalizeRoundCommand { round_id } => {
+                self.execute_finalize_round_command(command_source, round_id)
+            }
             PublishTaskCommand { task_config_path } => {
                 self.execute_publish_task_command(command_source, task_config_path)
             }
             ExecuteTaskCommand { task_id } => {
                 self.execute_task_command(command_source, task_id).await;
             }
+            ClaimTaskCommand { task_id } => self.execute_claim_task_command(command_source, task_id),
+            CancelTaskCommand { task_id } => self.execute_cancel_task_command(command_source, task_id),
+            SubmitTaskResultCommand {
+                task_id,
+                output,
+                digest,
+            } => self.execute_submit_task_result_command(command_source, task_id, output, digest),
+            StakeCommand { amount } => self.execute_stake_command(command_source, amount),
+            UnstakeCommand { amount } => self.execute_unstake_command(command_source, amount),
+            WithdrawUnstakedCommand => self.execute_withdraw_unstaked_command(command_source),
         }
     }
 
@@ -88,7 +122,15 @@ pub trait CommandExecutor {
     );
     fn execute_ipfs_add_file_command(&self, command_source: CommandSource, file_path: String);
     fn execute_start_round_command(&self, command_source: CommandSource);
-    fn execute_set_user_endpoint_command(&self, command_source: CommandSource, endpoint: String);
+    fn execute_finalize_round_command(&self, command_source: CommandSource, round_id: u32);
+    fn execute_set_user_endpoint_command(
+        &self,
+        command_source: CommandSource,
+        endpoint: String,
+        protocol: EndpointProtocol,
+    );
+    fn execute_set_user_prop_command(&self, command_source: CommandSource, name: String, value: u128);
+    fn execute_remove_user_prop_command(&self, command_source: CommandSource, name: String);
     fn execute_gas_price_command(&self, command_source: CommandSource);
     fn execute_get_user_endpoint_command(
         &self,
@@ -99,13 +141,27 @@ pub trait CommandExecutor {
     fn execute_query_specific_task_command(&self, command_source: CommandSource, task_id: u64);
     fn execute_count_events_command(&self, command_source: CommandSource);
     fn execute_count_tasks_command(&self, command_source: CommandSource);
-    fn execute_round_info_command(&self, command_source: CommandSource);
+    fn execute_list_task_types_command(&self, command_source: CommandSource, from: u32, limit: u32);
+    fn execute_round_info_command(&self, command_source: CommandSource, round_id: Option<u32>);
+    fn execute_list_rounds_command(&self, command_source: CommandSource, from: u32, limit: u32);
     fn execute_round_status_command(&self, command_source: CommandSource);
     fn execute_view_account_command(&self, command_source: CommandSource, account_id: AccountId);
     fn execute_status_command(&self, command_source: CommandSource);
     fn execute_network_status_command(&self, command_source: CommandSource);
     fn execute_help_command(&self, command_source: CommandSource, help: Vec<String>);
     async fn execute_task_command(&self, command_source: CommandSource, task_id: u64);
+    fn execute_claim_task_command(&self, command_source: CommandSource, task_id: u64);
+    fn execute_cancel_task_command(&self, command_source: CommandSource, task_id: u64);
+    fn execute_submit_task_result_command(
+        &self,
+        command_source: CommandSource,
+        task_id: u64,
+        output: String,
+        digest: String,
+    );
+    fn execute_stake_command(&self, command_source: CommandSource, amount: u128);
+    fn execute_unstake_command(&self, command_source: CommandSource, amount: u128);
+    fn execute_withdraw_unstaked_command(&self, command_source: CommandSource);
 }
 
 #[async_trait]
@@ -125,75 +181,39 @@ impl CommandExecutor for Backend {
                 Err(e) => anyhow::bail!("Failed to check if task config file exists: {}", e),
             }
 
-            // Read task config file
-            let task_config = tokio::fs::read_to_string(task_config_path).await?;
-            // it.log_string(format!("task_config: {task_config}"))?;
-            let task_config = serde_json::from_str::<RawTaskConfigFile>(&task_config)?;
+            // Read and encode task configs
+            let mut task_configs = Vec::new();
 
-            let actuator = if let Some(actuator) = get_actuator(&task_config.r#type) {
-                actuator
-            } else {
-                anyhow::bail!("Unsupported task type: {}", task_config.r#type);
-            };
-
-            // Encode task config
-            let task_config = match &task_config.input {
-                RawTaskInputSource::Ipfs { hash, file } => match (hash, file) {
-                    (None, None) => unreachable!(),
-                    (Some(_), Some(_)) => {
-                        anyhow::bail!(
-                            "Both hash and file are specified in task config {}",
-                            task_config_path.display()
-                        );
-                    }
-                    (Some(_hash), None) => task_config.build_task_config(None, &actuator)?,
-                    (None, Some(file)) => {
-                        let file_path = Path::new(file.as_str());
-                        let file_path = if file_path.is_absolute() {
-                            file_path.to_path_buf()
-                        } else {
-                            task_config_path.parent().unwrap().join(file_path)
-                        };
-
-                        if let Ok(true) = file_path.try_exists() {
-                            it.log_string(format!(
-                                "Uploading file to ipfs: {}",
-                                file_path.display()
-                            ))?;
-
-                            let file_cid = it.ipfs_client.add_file(file_path.as_path()).await?;
-
-                            it.log_string(format!("File uploaded to ipfs: {file_cid}"))?;
-
-                            task_config.build_task_config(Some(file_cid), &actuator)?
-                        } else {
-                            anyhow::bail!(
-                                "Task input file does not exist, {}",
-                                file_path.display()
-                            );
-                        }
-                    }
-                },
-                RawTaskInputSource::Link { .. } => {
-                    task_config.build_task_config(None, &actuator)?
-                }
-            };
+            for (task_config, path) in read_task_config_files(task_config_path).await? {
+                task_configs.push(build_task_config(&it, task_config, &path).await?);
+            }
 
             // Check if round is started
             let round_status = it.agent.get_round_status().await?;
 
-            if let RoundStatus::Pending = round_status {
+            if round_status != RoundStatus::Running {
                 anyhow::bail!("Round is not started yet. Please wait for the round to start.");
             }
 
-            // Publish task
-            let (gas, task_id) = it.agent.publish_task(task_config).await?;
+            // Publish tasks
+            if task_configs.len() == 1 {
+                let (gas, task_id) = it.agent.publish_task(task_configs.remove(0)).await?;
 
-            it.log_string(format!(
-                "Task published. Gas used: {}, Task ID: {:?}",
-                pretty_gas(gas),
-                task_id
-            ))?;
+                it.log_string(format!(
+                    "Task published. Gas used: {}, Task ID: {:?}",
+                    pretty_gas(gas),
+                    task_id
+                ))?;
+            } else {
+                let (gas, task_ids) = it.agent.publish_tasks(task_configs).await?;
+
+                it.log_string(format!(
+                    "{} tasks published. Gas used: {}, Task IDs: {:?}",
+                    task_ids.len(),
+                    pretty_gas(gas),
+                    task_ids
+                ))?;
+            }
 
             Ok(())
         });
@@ -202,9 +222,10 @@ impl CommandExecutor for Backend {
     fn execute_query_specific_task_command(&self, command_source: CommandSource, task_id: u64) {
         self.execute_command_block(command_source, async move |it: Backend| {
             // it.log_string(format!("task_id: {task_id}"))?;
-            let task = it.agent.query_specific_task(task_id.into()).await?;
-
-            it.log_string(format!("Task: {:?}", task))?;
+            match it.agent.query_specific_task(task_id.into()).await? {
+                Some(task) => it.log_string(format!("Task: {:?}", task))?,
+                None => it.log_string("Task not found".to_string())?,
+            }
 
             Ok(())
         });
@@ -215,15 +236,13 @@ impl CommandExecutor for Backend {
         let (tx , mut rx) = mpsc::channel(100);
         self.execute_command_block(command_source, async move |it: Backend| {
 
-            let task = it.agent.query_specific_task(task_id.into()).await?;
+            let Some(task) = it.agent.query_specific_task(task_id.into()).await? else {
+                anyhow::bail!("Task not found");
+            };
 
             // it.log_string(format!("OnchainTask: {task}"))?;
 
-            let actuator = if let Some(actuator) = get_actuator(&task.r#type) {
-                actuator
-            } else {
-                anyhow::bail!("Unsupported task type: {}", task.r#type);
-            };
+            let actuator = get_registered_actuator(&it, &task.r#type).await?;
 
             let task = task.to_rpc_task_config(task_id, &actuator)?;
 
@@ -275,6 +294,78 @@ impl CommandExecutor for Backend {
         });
     }
 
+    fn execute_claim_task_command(&self, command_source: CommandSource, task_id: u64) {
+        self.execute_command_block(command_source, async move |it: Backend| {
+            let gas = it.agent.claim_task(task_id.into()).await?;
+
+            it.log_string(format!("Task claimed. Gas used: {}", pretty_gas(gas)))?;
+
+            Ok(())
+        });
+    }
+
+    fn execute_cancel_task_command(&self, command_source: CommandSource, task_id: u64) {
+        self.execute_command_block(command_source, async move |it: Backend| {
+            let gas = it.agent.cancel_task(task_id.into()).await?;
+
+            it.log_string(format!("Task cancelled. Gas used: {}", pretty_gas(gas)))?;
+
+            Ok(())
+        });
+    }
+
+    fn execute_submit_task_result_command(
+        &self,
+        command_source: CommandSource,
+        task_id: u64,
+        output: String,
+        digest: String,
+    ) {
+        self.execute_command_block(command_source, async move |it: Backend| {
+            let gas = it
+                .agent
+                .submit_task_result(task_id.into(), output.as_str(), digest.as_str())
+                .await?;
+
+            it.log_string(format!("Task result submitted. Gas used: {}", pretty_gas(gas)))?;
+
+            Ok(())
+        });
+    }
+
+    fn execute_stake_command(&self, command_source: CommandSource, amount: u128) {
+        self.execute_command_block(command_source, async move |it: Backend| {
+            let (gas, stake) = it.agent.stake(amount).await?;
+
+            it.log_string(format!("Staked. Current stake: {stake}, gas used: {}", pretty_gas(gas)))?;
+
+            Ok(())
+        });
+    }
+
+    fn execute_unstake_command(&self, command_source: CommandSource, amount: u128) {
+        self.execute_command_block(command_source, async move |it: Backend| {
+            let (gas, stake) = it.agent.unstake(U256::from(amount)).await?;
+
+            it.log_string(format!("Unstaked. Current stake: {stake}, gas used: {}", pretty_gas(gas)))?;
+
+            Ok(())
+        });
+    }
+
+    fn execute_withdraw_unstaked_command(&self, command_source: CommandSource) {
+        self.execute_command_block(command_source, async move |it: Backend| {
+            let (gas, stake) = it.agent.withdraw_unstaked().await?;
+
+            it.log_string(format!(
+                "Unstaked balance withdrawn. Current stake: {stake}, gas used: {}",
+                pretty_gas(gas)
+            ))?;
+
+            Ok(())
+        });
+    }
+
     fn execute_ipfs_cat_file_command(&self, command_source: CommandSource, file_hash: String) {
         self.execute_command_block(command_source, async move |it: Backend| {
             let buffer = it.ipfs_client.cat_file(file_hash.as_str()).await?;
@@ -378,7 +469,7 @@ impl CommandExecutor for Backend {
         self.execute_command_block(command_source, async move |it: Backend| {
             let round_status = it.agent.get_round_status().await?;
 
-            let (gas, round_id) = if let RoundStatus::Pending = round_status {
+            let (gas, round_id) = if round_status != RoundStatus::Running {
                 it.agent.start_new_round().await?
             } else {
                 anyhow::bail!("Round is already started");
@@ -393,12 +484,74 @@ impl CommandExecutor for Backend {
         });
     }
 
-    fn execute_set_user_endpoint_command(&self, command_source: CommandSource, endpoint: String) {
+    fn execute_finalize_round_command(&self, command_source: CommandSource, round_id: u32) {
+        self.execute_command_block(command_source, async move |it: Backend| {
+            let mut total_gas = 0;
+
+            let summary = loop {
+                let (gas, summary) = it.agent.finalize_round(round_id, FINALIZE_BATCH_SIZE).await?;
+
+                total_gas += gas;
+
+                if let Some(summary) = summary {
+                    break summary;
+                }
+            };
+
+            it.log_string(format!(
+                "Round {round_id} finalized. Published: {}, completed: {}, expired: {}, bounty paid: {}, gas used: {}",
+                summary.tasks_published,
+                summary.tasks_completed,
+                summary.tasks_expired,
+                summary.bounty_paid,
+                pretty_gas(total_gas),
+            ))?;
+
+            Ok(())
+        });
+    }
+
+    fn execute_set_user_endpoint_command(
+        &self,
+        command_source: CommandSource,
+        endpoint: String,
+        protocol: EndpointProtocol,
+    ) {
         self.execute_command_block(command_source, async move |it:Backend| {
-            let gas = it.agent.set_user_endpoint(endpoint.as_str()).await?;
+            let endpoints = BTreeMap::from([(protocol, endpoint)]);
+            let gas = it.agent.set_user_endpoints(&endpoints).await?;
+
+            it.log_string(format!(
+                "User {protocol} endpoint set successfully. Gas used: {}",
+                pretty_gas(gas),
+            ))?;
+
+            Ok(())
+        })
+    }
+
+    fn execute_set_user_prop_command(&self, command_source: CommandSource, name: String, value: u128) {
+        self.execute_command_block(command_source, async move |it: Backend| {
+            let gas = it
+                .agent
+                .set_user_props(&[(name.clone(), U256::from(value))])
+                .await?;
 
             it.log_string(format!(
-                "User endpoint set successfully. Gas used: {}",
+                "User property {name} set to {value}. Gas used: {}",
+                pretty_gas(gas),
+            ))?;
+
+            Ok(())
+        })
+    }
+
+    fn execute_remove_user_prop_command(&self, command_source: CommandSource, name: String) {
+        self.execute_command_block(command_source, async move |it: Backend| {
+            let gas = it.agent.remove_user_prop(&name).await?;
+
+            it.log_string(format!(
+                "User property {name} removed. Gas used: {}",
                 pretty_gas(gas),
             ))?;
 
@@ -422,21 +575,28 @@ impl CommandExecutor for Backend {
         account_id: Option<AccountId>,
     ) {
         self.execute_command_block(command_source, async move |it: Backend| {
-            let endpoint = it.agent.get_user_endpoint(account_id).await?;
+            let endpoints = it.agent.get_user_endpoints(account_id).await?;
 
-            if let Some(endpoint) = endpoint {
-                it.log_string(format!("User endpoint: {endpoint}"))?;
-            } else {
+            if endpoints.is_empty() {
                 it.log_string("User endpoint is not set".to_string())?;
             }
 
+            for (protocol, endpoint) in endpoints {
+                it.log_string(format!("User {protocol} endpoint: {endpoint}"))?;
+            }
+
             Ok(())
         })
     }
 
     fn execute_query_events_command(&self, command_source: CommandSource, from: u32, count: u32) {
         self.execute_command_block(command_source, async move |it: Backend| {
-            let events = it.agent.query_events(from, count).await?;
+            let events = match it.agent.query_events(from, count).await? {
+                EventQueryResult::Ok { events } => events,
+                EventQueryResult::Pruned { earliest_available } => {
+                    anyhow::bail!("Events before {earliest_available} have been pruned");
+                }
+            };
 
             if events.is_empty() {
                 it.log_string("No events found".to_string())?;
@@ -470,7 +630,32 @@ impl CommandExecutor for Backend {
         })
     }
 
-    fn execute_round_info_command(&self, command_source: CommandSource) {
+    fn execute_list_task_types_command(&self, command_source: CommandSource, from: u32, limit: u32) {
+        self.execute_command_block(command_source, async move |it: Backend| {
+            let task_types = it.agent.list_task_types(from, limit).await?;
+
+            if task_types.is_empty() {
+                it.log_string("No task types registered".to_string())?;
+            } else {
+                for task_type in task_types {
+                    let supported = get_actuator(&task_type.name).is_some();
+
+                    it.log_string(format!(
+                        "{} v{} (schema: {}, supported: {}): {}",
+                        task_type.name,
+                        task_type.version,
+                        task_type.schema_cid,
+                        supported,
+                        task_type.description
+                    ))?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    fn execute_round_info_command(&self, command_source: CommandSource, round_id: Option<u32>) {
         // self.execute_command_block(command_source, async move |it| {
         //     let round_info = it.agent.get_round_info().await?;
 
@@ -480,15 +665,37 @@ impl CommandExecutor for Backend {
         // })
         self.execute_command_block(command_source, move |it: Backend| {
             Box::pin(async move {
-                let round_info = it.agent.get_round_info().await?;
-
-                it.log_string(format!("Round info: {:?}", round_info))?;
+                let round_info = match round_id {
+                    Some(round_id) => it.agent.get_round_info_by_id(round_id).await?,
+                    None => Some(it.agent.get_round_info().await?),
+                };
+
+                match round_info {
+                    Some(round_info) => it.log_string(format!("Round info: {:?}", round_info))?,
+                    None => it.log_string("Round not found".to_string())?,
+                }
     
                 async { Ok(()) }.await
             })
             })
     }
 
+    fn execute_list_rounds_command(&self, command_source: CommandSource, from: u32, limit: u32) {
+        self.execute_command_block(command_source, async move |it: Backend| {
+            let rounds = it.agent.list_rounds(from, limit).await?;
+
+            if rounds.is_empty() {
+                it.log_string("No rounds found".to_string())?;
+            } else {
+                for round in rounds {
+                    it.log_string(format!("{:?}", round))?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+
     fn execute_round_status_command(&self, command_source: CommandSource) {
         // self.execute_command_block(command_source, async move |it| {
         //     let round_status = it.agent.get_round_status().await?;
@@ -673,3 +880,118 @@ impl CommandExecutor for Backend {
         });
     }
 }
+
+/// Reads the task configs at `path`, which is either a task config file, a manifest
+/// holding a JSON array of task configs, or a directory of task config files.
+/// Every config comes with the path its input files are resolved against.
+async fn read_task_config_files(path: &Path) -> anyhow::Result<Vec<(RawTaskConfigFile, PathBuf)>> {
+    if path.is_dir() {
+        let mut paths = Vec::new();
+        let mut entries = tokio::fs::read_dir(path).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
+                paths.push(path);
+            }
+        }
+
+        paths.sort();
+
+        let mut task_configs = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let task_config = tokio::fs::read_to_string(&path).await?;
+            task_configs.push((serde_json::from_str::<RawTaskConfigFile>(&task_config)?, path));
+        }
+
+        if task_configs.is_empty() {
+            anyhow::bail!("No task config files found in {}", path.display());
+        }
+
+        return Ok(task_configs);
+    }
+
+    let task_config = tokio::fs::read_to_string(path).await?;
+    let task_config = serde_json::from_str::<serde_json::Value>(&task_config)?;
+
+    let task_configs = if task_config.is_array() {
+        serde_json::from_value::<Vec<RawTaskConfigFile>>(task_config)?
+    } else {
+        vec![serde_json::from_value::<RawTaskConfigFile>(task_config)?]
+    };
+
+    if task_configs.is_empty() {
+        anyhow::bail!("Task manifest {} is empty", path.display());
+    }
+
+    Ok(task_configs
+        .into_iter()
+        .map(|task_config| (task_config, path.to_path_buf()))
+        .collect())
+}
+
+/// Looks up the local actuator of a task type, which also has to be registered on chain.
+async fn get_registered_actuator(it: &Backend, r#type: &str) -> anyhow::Result<BoxedTaskActuator> {
+    let Some(actuator) = get_actuator(r#type) else {
+        anyhow::bail!("Unsupported task type: {}", r#type);
+    };
+
+    if it.agent.get_task_type(r#type).await?.is_none() {
+        anyhow::bail!("Task type {} is not registered on chain", r#type);
+    }
+
+    Ok(actuator)
+}
+
+/// Encodes a task config read from `task_config_path`, uploading its input file to IPFS if needed.
+async fn build_task_config(
+    it: &Backend,
+    task_config: RawTaskConfigFile,
+    task_config_path: &Path,
+) -> anyhow::Result<TaskConfig> {
+    let actuator = get_registered_actuator(it, &task_config.r#type).await?;
+
+    let task_config = match &task_config.input {
+        RawTaskInputSource::Ipfs { hash, file } => match (hash, file) {
+            (None, None) => unreachable!(),
+            (Some(_), Some(_)) => {
+                anyhow::bail!(
+                    "Both hash and file are specified in task config {}",
+                    task_config_path.display()
+                );
+            }
+            (Some(_hash), None) => task_config.build_task_config(None, &actuator)?,
+            (None, Some(file)) => {
+                let file_path = Path::new(file.as_str());
+                let file_path = if file_path.is_absolute() {
+                    file_path.to_path_buf()
+                } else {
+                    task_config_path.parent().unwrap().join(file_path)
+                };
+
+                if let Ok(true) = file_path.try_exists() {
+                    it.log_string(format!(
+                        "Uploading file to ipfs: {}",
+                        file_path.display()
+                    ))?;
+
+                    let file_cid = it.ipfs_client.add_file(file_path.as_path()).await?;
+
+                    it.log_string(format!("File uploaded to ipfs: {file_cid}"))?;
+
+                    task_config.build_task_config(Some(file_cid), &actuator)?
+                } else {
+                    anyhow::bail!(
+                        "Task input file does not exist, {}",
+                        file_path.display()
+                    );
+                }
+            }
+        },
+        RawTaskInputSource::Link { .. } => task_config.build_task_config(None, &actuator)?,
+    };
+
+    Ok(task_config)
+}
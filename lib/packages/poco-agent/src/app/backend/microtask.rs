@@ -1,7 +1,7 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use poco_types::types::event::Events;
+use poco_types::types::event::{EventQueryResult, Events};
 use tokio::task::JoinHandle;
 use crate::app::backend::event::ContractEventHandler;
 use poco_agent::agent::PocoAgent;
@@ -32,12 +32,20 @@ pub async fn event_microtask(
         interval.tick().await;
 
         match it.agent.query_events(offset, 10).await {
-            Ok(events) => {
+            Ok(EventQueryResult::Ok { events }) => {
                 offset += events.len() as u32;
 
                 events.into_iter().for_each(|e| it.handle_event(e));
 
             }
+            Ok(EventQueryResult::Pruned { earliest_available }) => {
+                log_string(
+                    &it.ui_sender,
+                    format!("Events {offset}..{earliest_available} were pruned, skipping them"),
+                );
+
+                offset = earliest_available;
+            }
             Err(error) => log_string(&it.ui_sender, format!("Error while querying events: {error}")),
         }
 
@@ -1,4 +1,5 @@
 use clap::error::ErrorKind;
+use poco_types::types::user::EndpointProtocol;
 
 use crate::app::backend::Backend;
 use crate::app::backend::command::{BackendCommand, commands};
@@ -6,7 +7,10 @@ use crate::app::backend::command::BackendCommand::{
     CountEventsCommand, CountTasksCommand, GasPriceCommand, GetUserEndpointCommand, HelpCommand, IpfsAddFileCommand,
     IpfsCatFileCommand, IpfsFileStatusCommand, IpfsGetFileCommand, NetworkStatusCommand,
     PublishTaskCommand, QueryEventsCommand, RoundInfoCommand, RoundStatusCommand,
-    SetUserEndpointCommand, StartRoundCommand, StatusCommand, ViewAccountCommand, QuerySpecificTaskCommand, ExecuteTaskCommand
+    SetUserEndpointCommand, StartRoundCommand, StatusCommand, ViewAccountCommand, QuerySpecificTaskCommand, ExecuteTaskCommand,
+    ClaimTaskCommand, SubmitTaskResultCommand, StakeCommand, UnstakeCommand,
+    WithdrawUnstakedCommand, FinalizeRoundCommand, ListRoundsCommand, CancelTaskCommand,
+    ListTaskTypesCommand, SetUserPropCommand, RemoveUserPropCommand
 };
 
 pub type ParseBackendCommandError = clap::Error;
@@ -57,9 +61,39 @@ impl CommandParser for Backend {
                 Ok(ViewAccountCommand { account_id })
             }
             Some(("round-status", _)) => Ok(RoundStatusCommand),
-            Some(("round-info", _)) => Ok(RoundInfoCommand),
+            Some(("round-info", args)) => {
+                let round_id = args
+                    .get_one::<String>("round-id")
+                    .map(|e| e.parse().expect("argument must be a valid u32"));
+
+                Ok(RoundInfoCommand { round_id })
+            }
+            Some(("list-rounds", args)) => {
+                let from = args
+                    .get_one::<String>("from")
+                    .and_then(|e| e.parse().ok())
+                    .expect("argument must be a valid u32");
+                let limit = args
+                    .get_one::<String>("limit")
+                    .and_then(|e| e.parse().ok())
+                    .expect("argument must be a valid u32");
+
+                Ok(ListRoundsCommand { from, limit })
+            }
             Some(("count-events", _)) => Ok(CountEventsCommand),
             Some(("count-tasks", _)) => Ok(CountTasksCommand),
+            Some(("list-task-types", args)) => {
+                let from = args
+                    .get_one::<String>("from")
+                    .and_then(|e| e.parse().ok())
+                    .expect("argument must be a valid u32");
+                let limit = args
+                    .get_one::<String>("limit")
+                    .and_then(|e| e.parse().ok())
+                    .expect("argument must be a valid u32");
+
+                Ok(ListTaskTypesCommand { from, limit })
+            }
             Some(("query-specific-task", args)) => {
                 let task_id = args
                     .get_one::<String>("task-id")
@@ -76,6 +110,36 @@ impl CommandParser for Backend {
 
                 Ok(ExecuteTaskCommand  { task_id })
             }
+            Some(("claim-task", args)) => {
+                let task_id = args
+                    .get_one::<String>("task-id")
+                    .and_then(|e| u64::from_str_radix(&e, 16).ok())
+                    .expect("argument must be a valid u64");
+
+                Ok(ClaimTaskCommand { task_id })
+            }
+            Some(("cancel-task", args)) => {
+                let task_id = args
+                    .get_one::<String>("task-id")
+                    .and_then(|e| u64::from_str_radix(&e, 16).ok())
+                    .expect("argument must be a valid u64");
+
+                Ok(CancelTaskCommand { task_id })
+            }
+            Some(("submit-result", args)) => {
+                let task_id = args
+                    .get_one::<String>("task-id")
+                    .and_then(|e| u64::from_str_radix(&e, 16).ok())
+                    .expect("argument must be a valid u64");
+                let output = args.get_one::<String>("output").cloned().unwrap();
+                let digest = args.get_one::<String>("digest").cloned().unwrap();
+
+                Ok(SubmitTaskResultCommand {
+                    task_id,
+                    output,
+                    digest,
+                })
+            }
             Some(("query-events", args)) => {
                 let from = args
                     .get_one::<String>("from")
@@ -110,8 +174,33 @@ impl CommandParser for Backend {
             }
             Some(("set-user-endpoint", args)) => {
                 let endpoint = args.get_one::<String>("endpoint").cloned().unwrap();
+                let protocol = match args.get_one::<String>("protocol").unwrap().as_str() {
+                    "grpc" => EndpointProtocol::Grpc,
+                    "http" => EndpointProtocol::Http,
+                    "ipfs" => EndpointProtocol::Ipfs,
+                    protocol => {
+                        return Err(clap::error::Error::raw(
+                            ErrorKind::InvalidValue,
+                            format!("Invalid endpoint protocol: {protocol}"),
+                        ))
+                    }
+                };
 
-                Ok(SetUserEndpointCommand { endpoint })
+                Ok(SetUserEndpointCommand { endpoint, protocol })
+            }
+            Some(("set-user-prop", args)) => {
+                let name = args.get_one::<String>("name").cloned().unwrap();
+                let value = args
+                    .get_one::<String>("value")
+                    .and_then(|e| e.parse().ok())
+                    .expect("argument must be a valid u128");
+
+                Ok(SetUserPropCommand { name, value })
+            }
+            Some(("remove-user-prop", args)) => {
+                let name = args.get_one::<String>("name").cloned().unwrap();
+
+                Ok(RemoveUserPropCommand { name })
             }
             Some(("ipfs", args)) => match args.subcommand() {
                 Some(("add", args)) => {
@@ -140,7 +229,32 @@ impl CommandParser for Backend {
                 }
                 _ => unreachable!("clap should have handled this"),
             },
+            Some(("stake", args)) => {
+                let amount = args
+                    .get_one::<String>("amount")
+                    .and_then(|e| e.parse().ok())
+                    .expect("argument must be a valid u128");
+
+                Ok(StakeCommand { amount })
+            }
+            Some(("unstake", args)) => {
+                let amount = args
+                    .get_one::<String>("amount")
+                    .and_then(|e| e.parse().ok())
+                    .expect("argument must be a valid u128");
+
+                Ok(UnstakeCommand { amount })
+            }
+            Some(("withdraw-unstaked", _)) => Ok(WithdrawUnstakedCommand),
             Some(("start-round", _)) => Ok(StartRoundCommand),
+            Some(("finalize-round", args)) => {
+                let round_id = args
+                    .get_one::<String>("round-id")
+                    .and_then(|e| e.parse().ok())
+                    .expect("argument must be a valid u32");
+
+                Ok(FinalizeRoundCommand { round_id })
+            }
             Some(("publish-task", args)) => {
                 let task_config_path = args.get_one::<String>("task-config-path").cloned().unwrap();
 
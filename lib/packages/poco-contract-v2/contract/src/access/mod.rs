@@ -0,0 +1,3 @@
+pub use role_manager::RoleManager;
+
+mod role_manager;
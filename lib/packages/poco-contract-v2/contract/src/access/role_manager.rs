@@ -0,0 +1,98 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::store::LookupMap;
+use near_sdk::AccountId;
+use poco_types::types::role::Role;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct RoleManager {
+    roles: LookupMap<AccountId, Vec<Role>>,
+}
+
+impl RoleManager {
+    pub fn new(owner: AccountId) -> Self {
+        let mut roles = LookupMap::new(b"role-manager:roles".to_vec());
+
+        roles.insert(owner, vec![Role::Owner]);
+
+        RoleManager { roles }
+    }
+
+    #[inline]
+    pub fn get_roles(&self, account: &AccountId) -> Vec<Role> {
+        self.roles.get(account).cloned().unwrap_or_default()
+    }
+
+    /// Whether the account holds `role`. Owners pass every role check.
+    #[inline]
+    pub fn has_role(&self, account: &AccountId, role: Role) -> bool {
+        self.roles
+            .get(account)
+            .map(|roles| roles.contains(&role) || roles.contains(&Role::Owner))
+            .unwrap_or(false)
+    }
+
+    /// Whether the account was granted `role` itself, without passing as an owner.
+    #[inline]
+    pub fn holds_role(&self, account: &AccountId, role: Role) -> bool {
+        self.roles
+            .get(account)
+            .is_some_and(|roles| roles.contains(&role))
+    }
+
+    /// Returns false if the account already holds the role.
+    pub fn grant_role(&mut self, account: &AccountId, role: Role) -> bool {
+        let roles = self.roles.entry(account.clone()).or_default();
+
+        if roles.contains(&role) {
+            return false;
+        }
+
+        roles.push(role);
+
+        true
+    }
+
+    /// Returns false if the account does not hold the role.
+    pub fn revoke_role(&mut self, account: &AccountId, role: Role) -> bool {
+        let Some(roles) = self.roles.get_mut(account) else {
+            return false;
+        };
+
+        let len = roles.len();
+        roles.retain(|e| *e != role);
+        let revoked = roles.len() != len;
+
+        if roles.is_empty() {
+            self.roles.remove(account);
+        }
+
+        revoked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::accounts;
+
+    use super::*;
+
+    #[test]
+    fn test_grant_and_revoke_role() {
+        let mut role_manager = RoleManager::new(accounts(0));
+
+        assert!(role_manager.has_role(&accounts(0), Role::Verifier));
+        assert!(!role_manager.has_role(&accounts(1), Role::Verifier));
+        assert!(!role_manager.holds_role(&accounts(0), Role::Verifier));
+
+        assert!(role_manager.grant_role(&accounts(1), Role::Verifier));
+        assert!(!role_manager.grant_role(&accounts(1), Role::Verifier));
+        assert!(role_manager.has_role(&accounts(1), Role::Verifier));
+        assert!(role_manager.holds_role(&accounts(1), Role::Verifier));
+        assert!(!role_manager.has_role(&accounts(1), Role::Pauser));
+
+        assert!(role_manager.revoke_role(&accounts(1), Role::Verifier));
+        assert!(!role_manager.revoke_role(&accounts(1), Role::Verifier));
+        assert!(!role_manager.has_role(&accounts(1), Role::Verifier));
+    }
+}
@@ -0,0 +1,210 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::store::{IterableMap, LookupMap};
+use near_sdk::AccountId;
+use poco_types::types::task::id::TaskId;
+use poco_types::types::task::status::TaskAssignment;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+/// Most stakers kept as assignment candidates, so publishing reads and draws from a pool
+/// of bounded size however many accounts stake.
+pub const MAX_ASSIGNMENT_CANDIDATES: u32 = 100;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct AssignmentManager {
+    assignments: LookupMap<TaskId, TaskAssignment>,
+    candidates: IterableMap<AccountId, u128>,
+}
+
+impl AssignmentManager {
+    pub fn new() -> Self {
+        AssignmentManager {
+            assignments: LookupMap::new(b"assignment-manager:assignments".to_vec()),
+            candidates: IterableMap::new(b"assignment-manager:candidates".to_vec()),
+        }
+    }
+
+    #[inline]
+    pub fn get_assignment(&self, task_id: &TaskId) -> Option<&TaskAssignment> {
+        self.assignments.get(task_id)
+    }
+
+    /// Whether the account may work on the task. Tasks nobody was drawn for are open to everyone.
+    #[inline]
+    pub fn is_assigned_worker(&self, task_id: &TaskId, account: &AccountId) -> bool {
+        self.assignments
+            .get(task_id)
+            .map(|assignment| {
+                assignment.workers.is_empty() || assignment.workers.contains(account)
+            })
+            .unwrap_or(true)
+    }
+
+    /// Whether the account may verify the task. Tasks nobody was drawn for are open to everyone.
+    #[inline]
+    pub fn is_assigned_verifier(&self, task_id: &TaskId, account: &AccountId) -> bool {
+        self.assignments
+            .get(task_id)
+            .map(|assignment| {
+                assignment.verifiers.is_empty() || assignment.verifiers.contains(account)
+            })
+            .unwrap_or(true)
+    }
+
+    pub fn assign(&mut self, task_id: &TaskId, assignment: TaskAssignment) {
+        self.assignments.insert(task_id.clone(), assignment);
+    }
+
+    /// Stakers tasks are drawn among, with their stake.
+    pub fn get_candidates(&self) -> impl Iterator<Item = (&AccountId, &u128)> {
+        self.candidates.iter()
+    }
+
+    /// Tracks the new stake of an account in the candidate pool. Accounts leave the pool when
+    /// their stake drops to zero, and once the pool is full a new account only joins it by
+    /// staking more than the smallest candidate, which it then replaces.
+    pub fn update_candidate(&mut self, account: &AccountId, stake: u128) {
+        if stake == 0 {
+            self.candidates.remove(account);
+            return;
+        }
+
+        if !self.candidates.contains_key(account)
+            && self.candidates.len() >= MAX_ASSIGNMENT_CANDIDATES
+        {
+            let smallest = self
+                .candidates
+                .iter()
+                .min_by_key(|(_, stake)| **stake)
+                .map(|(account, stake)| (account.clone(), *stake));
+
+            match smallest {
+                Some((smallest, smallest_stake)) if smallest_stake < stake => {
+                    self.candidates.remove(&smallest);
+                }
+                _ => return,
+            }
+        }
+
+        self.candidates.insert(account.clone(), stake);
+    }
+
+    /// Draws up to `count` distinct accounts, each with a probability proportional to its
+    /// weight among the accounts not drawn yet. Candidates are ordered by account id first,
+    /// so the draw only depends on the seed and the set of candidates. The generator is pinned
+    /// to ChaCha20 so recorded seeds replay to the same draw whatever the version of `rand`.
+    pub fn draw(seed: [u8; 32], mut candidates: Vec<(AccountId, u128)>, count: u32) -> Vec<AccountId> {
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        let mut drawn = Vec::new();
+
+        candidates.retain(|(_, weight)| *weight > 0);
+        candidates.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        while drawn.len() < count as usize && !candidates.is_empty() {
+            let total = candidates
+                .iter()
+                .fold(0u128, |total, (_, weight)| total.saturating_add(*weight));
+            let mut point = rng.gen_range(0..total);
+
+            let index = candidates
+                .iter()
+                .position(|(_, weight)| {
+                    if point < *weight {
+                        true
+                    } else {
+                        point -= *weight;
+                        false
+                    }
+                })
+                .unwrap_or(candidates.len() - 1);
+
+            drawn.push(candidates.remove(index).0);
+        }
+
+        drawn
+    }
+}
+
+impl Default for AssignmentManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::accounts;
+    use poco_types::types::round::BlockTimestamp;
+
+    use super::*;
+
+    #[test]
+    fn test_draw() {
+        let candidates = vec![
+            (accounts(0), 100),
+            (accounts(1), 0),
+            (accounts(2), 50),
+            (accounts(3), 10),
+        ];
+
+        let drawn = AssignmentManager::draw([7; 32], candidates.clone(), 2);
+        assert_eq!(drawn.len(), 2);
+        assert!(!drawn.contains(&accounts(1)));
+        assert_ne!(drawn[0], drawn[1]);
+
+        // The same seed and candidates always give the same draw, whatever their order.
+        let mut reversed = candidates.clone();
+        reversed.reverse();
+        assert_eq!(AssignmentManager::draw([7; 32], reversed, 2), drawn);
+
+        // Accounts without weight are never drawn, even when more are requested.
+        let all = AssignmentManager::draw([7; 32], candidates, 10);
+        assert_eq!(all.len(), 3);
+        assert!(!all.contains(&accounts(1)));
+    }
+
+    #[test]
+    fn test_assignment_checks() {
+        let mut manager = AssignmentManager::new();
+        let task_id = TaskId::from(1u64);
+
+        assert!(manager.is_assigned_worker(&task_id, &accounts(1)));
+
+        manager.assign(
+            &task_id,
+            TaskAssignment {
+                workers: vec![accounts(1)],
+                verifiers: vec![],
+                seed: [0; 32],
+                assigned_at: BlockTimestamp::from(0),
+            },
+        );
+
+        assert!(manager.is_assigned_worker(&task_id, &accounts(1)));
+        assert!(!manager.is_assigned_worker(&task_id, &accounts(2)));
+        assert!(manager.is_assigned_verifier(&task_id, &accounts(2)));
+    }
+
+    #[test]
+    fn test_candidate_pool_is_bounded() {
+        let mut manager = AssignmentManager::new();
+        let account = |index: u32| format!("staker-{index}.near").parse::<AccountId>().unwrap();
+
+        for index in 0..MAX_ASSIGNMENT_CANDIDATES {
+            manager.update_candidate(&account(index), 10 + index as u128);
+        }
+
+        // A full pool turns away smaller stakers and lets a larger one replace the smallest.
+        manager.update_candidate(&accounts(0), 5);
+        assert!(manager.get_candidates().all(|(candidate, _)| *candidate != accounts(0)));
+
+        manager.update_candidate(&accounts(1), 1_000);
+        assert_eq!(manager.get_candidates().count(), MAX_ASSIGNMENT_CANDIDATES as usize);
+        assert!(manager.get_candidates().any(|(candidate, _)| *candidate == accounts(1)));
+        assert!(manager.get_candidates().all(|(candidate, _)| *candidate != account(0)));
+
+        manager.update_candidate(&accounts(1), 0);
+        assert_eq!(manager.get_candidates().count(), MAX_ASSIGNMENT_CANDIDATES as usize - 1);
+    }
+}
@@ -0,0 +1,3 @@
+pub use assignment_manager::{AssignmentManager, MAX_ASSIGNMENT_CANDIDATES};
+
+mod assignment_manager;
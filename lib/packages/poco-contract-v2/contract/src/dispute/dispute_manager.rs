@@ -0,0 +1,137 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::store::LookupMap;
+use near_sdk::AccountId;
+use poco_types::types::dispute::{Dispute, DisputeStatus, DisputeVote};
+use poco_types::types::error::PocoError;
+use poco_types::types::task::id::TaskId;
+
+use crate::util::ensure;
+
+/// The latest dispute of every task, resolved ones are kept until the task is disputed again.
+#[derive(BorshDeserialize, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct DisputeManager {
+    disputes: LookupMap<TaskId, Dispute>,
+}
+
+impl DisputeManager {
+    pub fn new() -> Self {
+        DisputeManager {
+            disputes: LookupMap::new(b"dispute-manager:disputes".to_vec()),
+        }
+    }
+
+    #[inline]
+    pub fn get_dispute(&self, task_id: &TaskId) -> Option<&Dispute> {
+        self.disputes.get(task_id)
+    }
+
+    #[inline]
+    pub fn get_open_dispute(&self, task_id: &TaskId) -> Option<&Dispute> {
+        self.disputes
+            .get(task_id)
+            .filter(|dispute| dispute.status == DisputeStatus::Open)
+    }
+
+    pub fn open(&mut self, task_id: &TaskId, dispute: Dispute) {
+        ensure!(
+            self.get_open_dispute(task_id).is_none(),
+            PocoError::DisputeAlreadyOpen
+        );
+
+        self.disputes.insert(task_id.clone(), dispute);
+    }
+
+    /// Records the digest the verifier reproduced and returns the digest `threshold`
+    /// votes agree on, if there is one yet.
+    pub fn vote(
+        &mut self,
+        task_id: &TaskId,
+        verifier: AccountId,
+        digest: String,
+        threshold: usize,
+    ) -> Option<String> {
+        let dispute = self
+            .disputes
+            .get_mut(task_id)
+            .filter(|dispute| dispute.status == DisputeStatus::Open)
+            .unwrap_or_else(|| PocoError::NoOpenDispute.panic());
+
+        ensure!(
+            dispute.votes.iter().all(|vote| vote.verifier != verifier),
+            PocoError::AlreadyVoted
+        );
+
+        dispute.votes.push(DisputeVote { verifier, digest });
+
+        dispute.agreed_digest(threshold).map(|digest| digest.to_string())
+    }
+
+    pub fn close(&mut self, task_id: &TaskId, status: DisputeStatus) -> Dispute {
+        let dispute = self
+            .disputes
+            .get_mut(task_id)
+            .filter(|dispute| dispute.status == DisputeStatus::Open)
+            .unwrap_or_else(|| PocoError::NoOpenDispute.panic());
+
+        dispute.status = status;
+
+        dispute.clone()
+    }
+}
+
+impl Default for DisputeManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::accounts;
+    use poco_types::types::round::BlockTimestamp;
+    use poco_types::types::task::status::TaskStatus;
+    use poco_types::types::uint::U256;
+
+    use super::*;
+
+    #[test]
+    fn test_dispute_votes() {
+        let mut manager = DisputeManager::new();
+        let task_id = TaskId::new(1, 0);
+
+        manager.open(
+            &task_id,
+            Dispute {
+                challenger: accounts(0),
+                counter_digest: "b".to_string(),
+                bond: U256::from(100),
+                defendants: vec![accounts(1)],
+                disputed_digest: "a".to_string(),
+                prior_status: TaskStatus::Verified,
+                opened_at: BlockTimestamp::new(0),
+                votes: vec![],
+                status: DisputeStatus::Open,
+            },
+        );
+
+        assert_eq!(manager.vote(&task_id, accounts(2), "b".to_string(), 2), None);
+        assert_eq!(manager.vote(&task_id, accounts(3), "a".to_string(), 2), None);
+        assert_eq!(
+            manager.vote(&task_id, accounts(4), "b".to_string(), 2),
+            Some("b".to_string())
+        );
+
+        let dispute = manager.close(&task_id, DisputeStatus::Upheld);
+        assert_eq!(dispute.votes.len(), 3);
+        assert!(manager.get_open_dispute(&task_id).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:603")]
+    fn test_vote_without_dispute() {
+        let mut manager = DisputeManager::new();
+
+        manager.vote(&TaskId::new(1, 0), accounts(2), "a".to_string(), 1);
+    }
+}
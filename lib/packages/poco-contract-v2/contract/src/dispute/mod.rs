@@ -0,0 +1,3 @@
+pub use dispute_manager::DisputeManager;
+
+mod dispute_manager;
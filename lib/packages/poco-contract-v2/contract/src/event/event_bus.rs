@@ -1,17 +1,39 @@
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::store::Vector;
-use poco_types::types::event::{Events, IndexedEvent};
+use std::ops::Range;
 
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::store::LookupMap;
+use near_sdk::AccountId;
+use poco_types::types::event::{
+    EventFilter, EventKind, EventNonce, EventQueryResult, Events, IndexedEvent,
+};
+use poco_types::types::task::id::TaskId;
+
+use crate::util::CircularQueue;
+
+/// Keeps the most recent `retention` events. Event ids keep counting from the first event
+/// ever emitted, so ids handed out to clients stay valid after older events are dropped.
+///
+/// Ids of emitted events are also indexed by kind, account and task so that filtered
+/// queries do not have to scan the whole bus. An event is removed from the indexes when it
+/// is dropped from the bus, and keys left without events are removed altogether.
 #[derive(BorshDeserialize, BorshSerialize)]
 #[borsh(crate = "near_sdk::borsh")]
 pub struct EventBus {
-    events: Vector<Events>,
+    events: CircularQueue<Events>,
+    by_kind: LookupMap<EventKind, CircularQueue<EventNonce>>,
+    by_account: LookupMap<AccountId, CircularQueue<EventNonce>>,
+    by_task: LookupMap<TaskId, CircularQueue<EventNonce>>,
+    task_owners: LookupMap<TaskId, AccountId>,
 }
 
 impl EventBus {
-    pub fn new() -> Self {
+    pub fn new(retention: u32) -> Self {
         EventBus {
-            events: Vector::new(b"event-bus:events".to_vec()),
+            events: CircularQueue::new(retention as u64, b"event-bus:events".to_vec()),
+            by_kind: LookupMap::new(b"event-bus:by-kind".to_vec()),
+            by_account: LookupMap::new(b"event-bus:by-account".to_vec()),
+            by_task: LookupMap::new(b"event-bus:by-task".to_vec()),
+            task_owners: LookupMap::new(b"event-bus:task-owners".to_vec()),
         }
     }
 
@@ -19,39 +41,361 @@ impl EventBus {
     pub fn emit(&mut self, event: Events) {
         event.log_event();
 
-        self.events.push(event);
+        if self.events.is_full() {
+            self.unindex_earliest();
+        }
+
+        self.index(&event);
+        self.events.push_back(event);
+    }
+
+    /// Kind, accounts and task an event is indexed under. The owner of the task counts as
+    /// one of the accounts as long as the bus still holds events of the task.
+    fn index_keys(&self, event: &Events) -> (EventKind, Vec<AccountId>, Option<TaskId>) {
+        let mut accounts: Vec<AccountId> = event.accounts().into_iter().cloned().collect();
+
+        if let Some(owner) = event
+            .task_id()
+            .and_then(|task_id| self.task_owners.get(task_id))
+        {
+            accounts.push(owner.clone());
+        }
+
+        accounts.sort();
+        accounts.dedup();
+
+        (event.kind(), accounts, event.task_id().cloned())
+    }
+
+    fn index(&mut self, event: &Events) {
+        let event_id = self.len();
+        let retention = self.events.capacity();
+
+        if let Events::NewTaskEvent { task_id, task_config, .. } = event {
+            self.task_owners.insert(task_id.clone(), task_config.owner.clone());
+        }
+
+        let (kind, accounts, task_id) = self.index_keys(event);
+
+        self.by_kind
+            .entry(kind)
+            .or_insert_with(|| {
+                CircularQueue::new(
+                    retention,
+                    format!("event-bus:by-kind:{kind}").as_bytes().to_vec(),
+                )
+            })
+            .push_back(event_id);
+
+        if let Some(task_id) = task_id {
+            let prefix = format!("event-bus:by-task:{}", u64::from(&task_id));
+
+            self.by_task
+                .entry(task_id)
+                .or_insert_with(|| CircularQueue::new(retention, prefix.as_bytes().to_vec()))
+                .push_back(event_id);
+        }
+
+        for account in accounts {
+            let prefix = format!("event-bus:by-account:{account}");
+
+            self.by_account
+                .entry(account)
+                .or_insert_with(|| CircularQueue::new(retention, prefix.as_bytes().to_vec()))
+                .push_back(event_id);
+        }
     }
 
+    /// Drops the oldest event from the indexes, right before the bus evicts it.
+    fn unindex_earliest(&mut self) {
+        let event_id = self.earliest_available();
+
+        let Some(event) = self.events.get(event_id as u64) else {
+            return;
+        };
+
+        let (kind, accounts, task_id) = self.index_keys(event);
+
+        unindex(&mut self.by_kind, &kind, event_id);
+
+        for account in &accounts {
+            unindex(&mut self.by_account, account, event_id);
+        }
+
+        if let Some(task_id) = task_id {
+            unindex(&mut self.by_task, &task_id, event_id);
+
+            if !self.by_task.contains_key(&task_id) {
+                self.task_owners.remove(&task_id);
+            }
+        }
+    }
+
+    /// Number of events emitted so far, including the pruned ones.
     #[inline]
     pub fn len(&self) -> u32 {
-        self.events.len()
+        self.events.total_count() as u32
     }
 
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.events.is_empty()
+        self.len() == 0
     }
 
+    /// Id of the oldest event still stored.
     #[inline]
-    pub fn query_event(&self, from: u32, count: u32) -> Vec<IndexedEvent> {
-        let from = from as usize;
-        let count = count as usize;
-
-        self.events
-            .iter()
-            .enumerate()
-            .skip(from)
-            .take(count)
-            .map(|e| IndexedEvent {
-                event_id: e.0 as u32,
-                payload: e.1.clone(),
+    pub fn earliest_available(&self) -> EventNonce {
+        (self.events.total_count() - self.events.len()) as EventNonce
+    }
+
+    #[inline]
+    pub fn query_event(&self, from: u32, count: u32) -> EventQueryResult {
+        let earliest_available = self.earliest_available();
+
+        if from < earliest_available {
+            return EventQueryResult::Pruned { earliest_available };
+        }
+
+        let events = (from as u64..self.len() as u64)
+            .take(count as usize)
+            .filter_map(|event_id| {
+                self.events.get(event_id).map(|payload| IndexedEvent {
+                    event_id: event_id as u32,
+                    payload: payload.clone(),
+                })
             })
-            .collect()
+            .collect();
+
+        EventQueryResult::Ok { events }
+    }
+
+    /// Queries up to `count` events within `range` that match `filter`. The round of the
+    /// filter is not looked at here, callers narrow `range` to the round's event window.
+    pub fn query_filtered_event(
+        &self,
+        filter: &EventFilter,
+        range: Range<EventNonce>,
+        count: u32,
+    ) -> EventQueryResult {
+        let earliest_available = self.earliest_available();
+
+        if range.start < earliest_available {
+            return EventQueryResult::Pruned { earliest_available };
+        }
+
+        let range = range.start..range.end.min(self.len());
+
+        // Walk the smallest index the filter allows, the other criteria are checked per event.
+        let index = if let Some(task_id) = &filter.task_id {
+            Some(self.by_task.get(task_id))
+        } else if let Some(account) = &filter.account {
+            Some(self.by_account.get(account))
+        } else {
+            filter.kind.map(|kind| self.by_kind.get(&kind))
+        };
+
+        let candidates: Box<dyn Iterator<Item = EventNonce>> = match index {
+            Some(Some(index)) => {
+                let start = lower_bound(index, range.start);
+
+                Box::new(
+                    (start..index.total_count())
+                        .filter_map(|position| index.get(position).copied())
+                        .take_while(|event_id| *event_id < range.end),
+                )
+            }
+            Some(None) => Box::new(std::iter::empty()),
+            None => Box::new(range),
+        };
+
+        let events = candidates
+            .filter_map(|event_id| {
+                self.events
+                    .get(event_id as u64)
+                    .filter(|payload| self.matches(filter, payload))
+                    .map(|payload| IndexedEvent {
+                        event_id,
+                        payload: payload.clone(),
+                    })
+            })
+            .take(count as usize)
+            .collect();
+
+        EventQueryResult::Ok { events }
+    }
+
+    fn matches(&self, filter: &EventFilter, event: &Events) -> bool {
+        if filter.kind.is_some_and(|kind| kind != event.kind()) {
+            return false;
+        }
+
+        if let Some(task_id) = &filter.task_id {
+            if event.task_id() != Some(task_id) {
+                return false;
+            }
+        }
+
+        if let Some(account) = &filter.account {
+            let is_task_owner = event
+                .task_id()
+                .and_then(|task_id| self.task_owners.get(task_id))
+                .is_some_and(|owner| owner == account);
+
+            if !is_task_owner && !event.accounts().contains(&account) {
+                return false;
+            }
+        }
+
+        true
     }
 }
 
-impl Default for EventBus {
-    fn default() -> Self {
-        Self::new()
+/// Removes `event_id` from the front of the index under `key`, and the key itself once it
+/// has no events left.
+fn unindex<K>(
+    indexes: &mut LookupMap<K, CircularQueue<EventNonce>>,
+    key: &K,
+    event_id: EventNonce,
+) where
+    K: BorshSerialize + Ord + Clone,
+{
+    let Some(index) = indexes.get_mut(key) else {
+        return;
+    };
+
+    if index.front() == Some(&event_id) {
+        index.remove_front();
+    }
+
+    if index.is_empty() {
+        if let Some(mut index) = indexes.remove(key) {
+            index.clear();
+        }
+    }
+}
+
+/// Position of the first id in the sorted `index` that is not less than `event_id`.
+fn lower_bound(index: &CircularQueue<EventNonce>, event_id: EventNonce) -> u64 {
+    let (mut low, mut high) = (index.total_count() - index.len(), index.total_count());
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        if index.get(mid).is_some_and(|id| *id < event_id) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+
+    low
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::accounts;
+
+    use super::*;
+
+    fn event(endpoint: &str) -> Events {
+        Events::UserProfileFieldUpdateEvent {
+            user_id: accounts(0),
+            field: "endpoint".to_string(),
+            value: endpoint.to_string(),
+        }
+    }
+
+    fn event_ids(result: EventQueryResult) -> Vec<u32> {
+        match result {
+            EventQueryResult::Ok { events } => events.iter().map(|e| e.event_id).collect(),
+            EventQueryResult::Pruned { .. } => panic!("events should not be pruned"),
+        }
+    }
+
+    #[test]
+    fn test_event_retention() {
+        let mut event_bus = EventBus::new(3);
+
+        for i in 0..5 {
+            event_bus.emit(event(&i.to_string()));
+        }
+
+        assert_eq!(event_bus.len(), 5);
+        assert_eq!(event_bus.earliest_available(), 2);
+        assert_eq!(event_ids(event_bus.query_event(2, 10)), vec![2, 3, 4]);
+        assert_eq!(event_ids(event_bus.query_event(3, 1)), vec![3]);
+        assert_eq!(event_ids(event_bus.query_event(5, 1)), Vec::<u32>::new());
+
+        assert!(matches!(
+            event_bus.query_event(1, 10),
+            EventQueryResult::Pruned {
+                earliest_available: 2
+            }
+        ));
+    }
+
+    #[test]
+    fn test_filtered_query() {
+        let mut event_bus = EventBus::new(10);
+
+        event_bus.emit(event("a"));
+        event_bus.emit(Events::NewRoundEvent { round_id: 1 });
+        event_bus.emit(Events::UserProfileFieldUpdateEvent {
+            user_id: accounts(1),
+            field: "endpoint".to_string(),
+            value: "b".to_string(),
+        });
+        event_bus.emit(event("c"));
+
+        let filter = EventFilter {
+            account: Some(accounts(0)),
+            ..Default::default()
+        };
+        assert_eq!(event_ids(event_bus.query_filtered_event(&filter, 0..10, 10)), vec![0, 3]);
+        assert_eq!(event_ids(event_bus.query_filtered_event(&filter, 1..10, 10)), vec![3]);
+        assert_eq!(event_ids(event_bus.query_filtered_event(&filter, 0..10, 1)), vec![0]);
+
+        let filter = EventFilter {
+            kind: Some(EventKind::NewRoundEvent),
+            ..Default::default()
+        };
+        assert_eq!(event_ids(event_bus.query_filtered_event(&filter, 0..10, 10)), vec![1]);
+
+        let filter = EventFilter {
+            kind: Some(EventKind::NewRoundEvent),
+            account: Some(accounts(0)),
+            ..Default::default()
+        };
+        assert_eq!(event_ids(event_bus.query_filtered_event(&filter, 0..10, 10)), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_index_pruning() {
+        let mut event_bus = EventBus::new(2);
+
+        event_bus.emit(Events::UserProfileFieldUpdateEvent {
+            user_id: accounts(1),
+            field: "endpoint".to_string(),
+            value: "a".to_string(),
+        });
+
+        for i in 0..4 {
+            event_bus.emit(event(&i.to_string()));
+        }
+
+        assert!(!event_bus.by_account.contains_key(&accounts(1)));
+
+        let index = event_bus.by_account.get(&accounts(0)).unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.front(), Some(&3));
+
+        let index = event_bus.by_kind.get(&EventKind::UserProfileFieldUpdateEvent).unwrap();
+        assert_eq!(index.len(), 2);
+
+        let filter = EventFilter {
+            account: Some(accounts(0)),
+            ..Default::default()
+        };
+        assert_eq!(event_ids(event_bus.query_filtered_event(&filter, 3..10, 10)), vec![3, 4]);
     }
 }
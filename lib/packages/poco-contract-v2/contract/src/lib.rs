@@ -1,17 +1,45 @@
-use near_sdk::{AccountId, near_bindgen};
+// The bindings `near_bindgen` generates for `new` take one argument more than it does.
+#![allow(clippy::too_many_arguments)]
+
+use std::collections::BTreeMap;
+
+use near_sdk::{AccountId, NearToken, PanicOnDefault, Promise, StorageUsage, near_bindgen};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use poco_types::types::event::{Events, IndexedEvent};
-use poco_types::types::round::{RoundId, RoundInfo, RoundStatus};
+use poco_types::types::config::{
+    ContractConfig, DEFAULT_ASSIGNED_VERIFIERS, DEFAULT_ASSIGNED_WORKERS,
+    DEFAULT_CHALLENGE_WINDOW_IN_MS, DEFAULT_DISPUTE_TIMEOUT_IN_MS, DEFAULT_EVENT_RETENTION,
+    DEFAULT_MAX_CONFIG_BYTES, DEFAULT_MIN_DISPUTE_COMMITTEE, DEFAULT_UNBONDING_ROUNDS,
+    DEFAULT_VERIFICATION_TIMEOUT_IN_MS,
+};
+use poco_types::types::dispute::{Dispute, DisputeStatus};
+use poco_types::types::error::PocoError;
+use poco_types::types::event::{EventFilter, EventQueryResult, Events, StakeAction};
+use poco_types::types::role::Role;
+use poco_types::types::round::{
+    BlockTimestamp, RoundDuration, RoundId, RoundInfo, RoundStatus, RoundSummary,
+};
 use poco_types::types::task::id::TaskId;
-use poco_types::types::task::{TaskConfig, OnChainTaskConfig};
-use poco_types::types::user::UserProfile;
+use poco_types::types::task::status::{TaskAssignment, TaskResult, TaskStatus};
+use poco_types::types::task::task_type::TaskType;
+use poco_types::types::task::{
+    OnChainTaskConfig, TaskConfig, TaskOffer, TaskRequirement,
+};
+use poco_types::types::uint::U256;
+use poco_types::types::user::{EndpointProtocol, Reputation, UnbondingStake, UserProfile};
 
 use event::EventBus;
 
+use crate::access::RoleManager;
+use crate::assignment::AssignmentManager;
+use crate::dispute::DisputeManager;
 use crate::round::RoundManager;
-use crate::task::TaskManager;
+use crate::task::{QuorumOutcome, TaskManager, TaskTypeRegistry};
 use crate::user::UserManager;
+use crate::util::ensure;
 
+pub mod access;
+pub mod assignment;
+pub mod dispute;
 pub mod event;
 pub mod round;
 pub mod task;
@@ -19,33 +47,251 @@ pub mod user;
 pub mod util;
 
 #[near_bindgen]
-#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Contract {
+    config: ContractConfig,
+    paused: bool,
+    role_manager: RoleManager,
     user_manager: UserManager,
     round_manager: RoundManager,
     task_manager: TaskManager,
+    task_types: TaskTypeRegistry,
+    assignment_manager: AssignmentManager,
+    dispute_manager: DisputeManager,
     event_bus: EventBus,
 }
 
-// Define the default, which automatically initializes the contract
-impl Default for Contract {
-    fn default() -> Self {
+#[near_bindgen]
+impl Contract {
+    #[init]
+    pub fn new(
+        owner: AccountId,
+        round_duration: RoundDuration,
+        min_stake: Option<U256>,
+        max_config_bytes: Option<u32>,
+        unbonding_rounds: Option<RoundId>,
+        event_retention: Option<u32>,
+        task_types: Option<Vec<TaskType>>,
+    ) -> Self {
         let initial_round_id = 0;
-        let initial_round_duration = (1000 * 60 * 30).into();
+        let event_retention = event_retention.unwrap_or(DEFAULT_EVENT_RETENTION);
+        let task_types = task_types.unwrap_or_default();
+
+        for task_type in &task_types {
+            if let Err(error) = task_type.validate() {
+                error.panic_with(&task_type.name);
+            }
+        }
 
         Self {
+            config: ContractConfig {
+                round_duration,
+                min_stake: min_stake.unwrap_or_default(),
+                max_config_bytes: max_config_bytes.unwrap_or(DEFAULT_MAX_CONFIG_BYTES),
+                unbonding_rounds: unbonding_rounds.unwrap_or(DEFAULT_UNBONDING_ROUNDS),
+                event_retention,
+                assigned_workers: DEFAULT_ASSIGNED_WORKERS,
+                assigned_verifiers: DEFAULT_ASSIGNED_VERIFIERS,
+                challenge_window: RoundDuration::new(DEFAULT_CHALLENGE_WINDOW_IN_MS),
+                min_dispute_bond: U256::zero(),
+                dissent_slash: U256::zero(),
+                dispute_timeout: RoundDuration::new(DEFAULT_DISPUTE_TIMEOUT_IN_MS),
+                min_dispute_committee: DEFAULT_MIN_DISPUTE_COMMITTEE,
+                dispute_slash: U256::zero(),
+                verification_timeout: RoundDuration::new(DEFAULT_VERIFICATION_TIMEOUT_IN_MS),
+            },
+            paused: false,
+            role_manager: RoleManager::new(owner),
             user_manager: UserManager::new(),
-            round_manager: RoundManager::new(initial_round_id, initial_round_duration),
+            round_manager: RoundManager::new(initial_round_id, round_duration),
             task_manager: TaskManager::new(),
-            event_bus: EventBus::new(),
+            task_types: TaskTypeRegistry::new(task_types),
+            assignment_manager: AssignmentManager::new(),
+            dispute_manager: DisputeManager::new(),
+            event_bus: EventBus::new(event_retention),
         }
     }
-}
 
-#[near_bindgen]
-impl Contract {
+    pub fn version(&self) -> String {
+        env!("CARGO_PKG_VERSION").to_string()
+    }
+
+    pub fn get_config(&self) -> ContractConfig {
+        self.config.clone()
+    }
+
+    /// Changes the duration of rounds started after this call.
+    pub fn set_round_duration(&mut self, round_duration: RoundDuration) {
+        self.assert_role(Role::Owner);
+
+        self.config.round_duration = round_duration;
+    }
+
+    pub fn set_min_stake(&mut self, min_stake: U256) {
+        self.assert_role(Role::Owner);
+
+        self.config.min_stake = min_stake;
+    }
+
+    pub fn set_max_config_bytes(&mut self, max_config_bytes: u32) {
+        self.assert_role(Role::Owner);
+
+        self.config.max_config_bytes = max_config_bytes;
+    }
+
+    /// Sets how many workers and verifiers are drawn for tasks published from now on.
+    pub fn set_assignment_sizes(&mut self, assigned_workers: u32, assigned_verifiers: u32) {
+        self.assert_role(Role::Owner);
+
+        self.config.assigned_workers = assigned_workers;
+        self.config.assigned_verifiers = assigned_verifiers;
+    }
+
+    /// Changes the challenge window, it applies to every result not settled yet.
+    pub fn set_challenge_window(&mut self, challenge_window: RoundDuration) {
+        self.assert_role(Role::Owner);
+
+        self.config.challenge_window = challenge_window;
+    }
+
+    pub fn set_min_dispute_bond(&mut self, min_dispute_bond: U256) {
+        self.assert_role(Role::Owner);
+
+        self.config.min_dispute_bond = min_dispute_bond;
+    }
+
+    pub fn set_dissent_slash(&mut self, dissent_slash: U256) {
+        self.assert_role(Role::Owner);
+        ensure!(dissent_slash <= U256::from(u128::MAX), PocoError::AmountTooLarge);
+
+        self.config.dissent_slash = dissent_slash;
+    }
+
+    /// Changes the dispute timeout, it applies to disputes already open.
+    pub fn set_dispute_timeout(&mut self, dispute_timeout: RoundDuration) {
+        self.assert_role(Role::Owner);
+
+        self.config.dispute_timeout = dispute_timeout;
+    }
+
+    /// Sets how many verifiers a task needs for its result to be disputed, at least one.
+    pub fn set_min_dispute_committee(&mut self, min_dispute_committee: u32) {
+        self.assert_role(Role::Owner);
+
+        self.config.min_dispute_committee = min_dispute_committee.max(1);
+    }
+
+    pub fn set_dispute_slash(&mut self, dispute_slash: U256) {
+        self.assert_role(Role::Owner);
+        ensure!(dispute_slash <= U256::from(u128::MAX), PocoError::AmountTooLarge);
+
+        self.config.dispute_slash = dispute_slash;
+    }
+
+    /// Changes the verification timeout, it applies to results already submitted.
+    pub fn set_verification_timeout(&mut self, verification_timeout: RoundDuration) {
+        self.assert_role(Role::Owner);
+
+        self.config.verification_timeout = verification_timeout;
+    }
+
+    /// Registers a task type, or updates an already registered one to a higher version.
+    pub fn register_task_type(&mut self, task_type: TaskType) {
+        self.assert_role(Role::Governance);
+
+        if let Err(error) = task_type.validate() {
+            error.panic_with(&task_type.name);
+        }
+
+        if let Some(registered) = self.task_types.get(&task_type.name) {
+            ensure!(
+                task_type.version > registered.version,
+                PocoError::TaskTypeVersionNotRaised
+            );
+        }
+
+        self.task_types.register(task_type.clone());
+
+        self.event_bus.emit(Events::TaskTypeRegisteredEvent { task_type });
+    }
+
+    /// Rejects new tasks of the type, tasks already published are not affected.
+    pub fn unregister_task_type(&mut self, name: String) {
+        self.assert_role(Role::Governance);
+
+        if self.task_types.unregister(&name).is_none() {
+            PocoError::UnknownTaskType.panic_with(&name);
+        }
+
+        self.event_bus.emit(Events::TaskTypeUnregisteredEvent { name });
+    }
+
+    pub fn get_task_type(&self, name: String) -> Option<TaskType> {
+        self.task_types.get(&name).cloned()
+    }
+
+    pub fn list_task_types(&self, from: u32, limit: u32) -> Vec<TaskType> {
+        self.task_types.list(from, limit)
+    }
+
+    pub fn grant_role(&mut self, account_id: AccountId, role: Role) {
+        self.assert_role(Role::Owner);
+
+        if self.role_manager.grant_role(&account_id, role) {
+            self.event_bus.emit(Events::RoleGrantedEvent {
+                user_id: account_id,
+                role,
+            });
+        }
+    }
+
+    pub fn revoke_role(&mut self, account_id: AccountId, role: Role) {
+        self.assert_role(Role::Owner);
+
+        ensure!(
+            !(role == Role::Owner && account_id == near_sdk::env::predecessor_account_id()),
+            PocoError::OwnershipRevocation
+        );
+
+        if self.role_manager.revoke_role(&account_id, role) {
+            self.event_bus.emit(Events::RoleRevokedEvent {
+                user_id: account_id,
+                role,
+            });
+        }
+    }
+
+    pub fn has_role(&self, account_id: AccountId, role: Role) -> bool {
+        self.role_manager.has_role(&account_id, role)
+    }
+
+    pub fn get_roles(&self, account_id: AccountId) -> Vec<Role> {
+        self.role_manager.get_roles(&account_id)
+    }
+
+    /// Blocks publishing and claiming tasks until `unpause` is called.
+    pub fn pause(&mut self) {
+        self.assert_role(Role::Pauser);
+
+        self.paused = true;
+    }
+
+    pub fn unpause(&mut self) {
+        self.assert_role(Role::Pauser);
+
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
     pub fn start_new_round(&mut self) -> RoundId {
-        let new_round_id = self.round_manager.start_new_round(self.event_bus.len());
+        self.assert_role(Role::RoundKeeper);
+
+        let new_round_id = self
+            .round_manager
+            .start_new_round(self.event_bus.len(), self.config.round_duration);
 
         self.event_bus.emit(Events::NewRoundEvent {
             round_id: new_round_id,
@@ -83,6 +329,122 @@ impl Contract {
         }
     }
 
+    pub fn get_round_info_by_id(&self, round_id: RoundId) -> Option<RoundInfo> {
+        let record = self.round_manager.get_round_record(round_id)?;
+        let status = self.round_manager.get_round_status_by_id(round_id)?;
+        let event_end = record.event_end.unwrap_or(self.event_bus.len());
+
+        Some(RoundInfo {
+            id: round_id,
+            status,
+            start_time: record.start_time,
+            duration: record.duration,
+            event_count: event_end - record.event_offset,
+            event_offset: record.event_offset,
+            task_count: self.task_manager.round_count(round_id),
+        })
+    }
+
+    /// Lists up to `limit` started rounds, beginning at round `from`.
+    pub fn list_rounds(&self, from: RoundId, limit: u32) -> Vec<RoundInfo> {
+        (from.max(1)..=self.round_manager.get_round_id())
+            .take(limit as usize)
+            .filter_map(|round_id| self.get_round_info_by_id(round_id))
+            .collect()
+    }
+
+    /// Expires the tasks of an ended round that were never claimed or never submitted,
+    /// refunding their owners, settles verified tasks and stores the round totals.
+    /// Verified tasks still within their challenge window are left to `settle_task`, and
+    /// claimed tasks whose execution timeout has not passed yet are left to `expire_task`.
+    /// Submitted results past their verification timeout are accepted, and settled once
+    /// their challenge window has closed. Expired disputes are closed first, restoring
+    /// their tasks.
+    ///
+    /// Each call goes through at most `limit` tasks, resuming after the tasks of the previous
+    /// calls. The round stays `Finalizing` and `None` is returned until its last task is done,
+    /// then the call returns the round totals.
+    pub fn finalize_round(&mut self, round_id: RoundId, limit: u32) -> Option<RoundSummary> {
+        self.assert_role(Role::RoundKeeper);
+
+        ensure!(
+            self.round_manager.get_round_status_by_id(round_id) == Some(RoundStatus::Finalizing),
+            PocoError::RoundNotFinalizing
+        );
+
+        let tasks_published = self.task_manager.round_count(round_id);
+        let mut progress = self.round_manager.get_finalization_progress(round_id);
+        let end = progress.next_task_nonce.saturating_add(limit).min(tasks_published);
+
+        for task_nonce in progress.next_task_nonce..end {
+            let task_id = TaskId::new(round_id, task_nonce);
+
+            if self.is_dispute_expired(&task_id) {
+                self.expire_open_dispute(&task_id);
+            }
+
+            let status = self.task_manager.get_task_status(&task_id).unwrap();
+
+            match status {
+                TaskStatus::Claimed if !self.is_claim_expired(&task_id) => {}
+                TaskStatus::Published | TaskStatus::Claimed | TaskStatus::Rejected => {
+                    self.expire(&task_id);
+
+                    progress.tasks_expired += 1;
+                }
+                TaskStatus::Verified if self.is_challenge_window_open(&task_id) => {}
+                TaskStatus::Verified => {
+                    self.settle(&task_id);
+
+                    progress.tasks_completed += 1;
+                }
+                TaskStatus::Submitted if self.is_verification_expired(&task_id) => {
+                    self.accept(&task_id);
+
+                    if !self.is_challenge_window_open(&task_id) {
+                        self.settle(&task_id);
+
+                        progress.tasks_completed += 1;
+                    }
+                }
+                TaskStatus::Settled => progress.tasks_completed += 1,
+                TaskStatus::Expired => progress.tasks_expired += 1,
+                TaskStatus::Submitted | TaskStatus::Disputed | TaskStatus::Cancelled => {}
+            }
+
+            progress.bounty_paid += self.task_manager.get_task_state(&task_id).unwrap().bounty_paid;
+        }
+
+        if end < tasks_published {
+            progress.next_task_nonce = end;
+            self.round_manager.set_finalization_progress(round_id, progress);
+
+            return None;
+        }
+
+        let summary = RoundSummary {
+            round_id,
+            tasks_published,
+            tasks_completed: progress.tasks_completed,
+            tasks_expired: progress.tasks_expired,
+            bounty_paid: progress.bounty_paid,
+            finalized_at: near_sdk::env::block_timestamp_ms().into(),
+        };
+
+        self.round_manager.finalize_round(summary.clone());
+
+        self.event_bus.emit(Events::RoundFinalizedEvent {
+            round_id,
+            summary: summary.clone(),
+        });
+
+        Some(summary)
+    }
+
+    pub fn get_round_summary(&self, round_id: RoundId) -> Option<RoundSummary> {
+        self.round_manager.get_round_summary(round_id).cloned()
+    }
+
     pub fn get_round_id_and_status(&self) -> (RoundId, RoundStatus) {
         (
             self.round_manager.get_round_id(),
@@ -94,7 +456,7 @@ impl Contract {
         self.event_bus.len()
     }
 
-    pub fn query_specific_task(&self, round_id: u32, task_nounce: u32) -> OnChainTaskConfig {
+    pub fn query_specific_task(&self, round_id: u32, task_nounce: u32) -> Option<OnChainTaskConfig> {
         self.task_manager.query_specific_task(round_id, task_nounce)
     }
 
@@ -102,74 +464,1858 @@ impl Contract {
     //     self.task_manager.query_specific_task(task_id)
     // }
 
-    pub fn show_tasks(&self, round_id: u32) {
-        self.task_manager.show_tasks(round_id);
+    pub fn list_tasks(&self, round_id: RoundId, from: u32, limit: u32) -> Vec<OnChainTaskConfig> {
+        self.task_manager.list_tasks(round_id, from, limit)
     }
 
-    pub fn query_events(&self, from: u32, count: u32) -> Vec<IndexedEvent> {
-        self.event_bus.query_event(from, count)
+    pub fn list_tasks_by_owner(&self, account: AccountId, from: u32, limit: u32) -> Vec<OnChainTaskConfig> {
+        self.task_manager.list_tasks_by_owner(&account, from, limit)
     }
 
-    pub fn query_round_events(&self, round_offset: u32, count: u32) -> Vec<IndexedEvent> {
-        let from = round_offset - self.round_manager.get_round_event_offset();
+    pub fn list_tasks_by_worker(&self, account: AccountId, from: u32, limit: u32) -> Vec<OnChainTaskConfig> {
+        self.task_manager.list_tasks_by_worker(&account, from, limit)
+    }
 
+    pub fn query_events(&self, from: u32, count: u32) -> EventQueryResult {
         self.event_bus.query_event(from, count)
     }
 
-    pub fn get_user_profile(&self, account: AccountId) -> UserProfile {
+    /// Queries up to `count` events matching `filter`, starting at event id `from`.
+    pub fn query_filtered_events(&self, filter: EventFilter, from: u32, count: u32) -> EventQueryResult {
+        let range = match filter.round_id {
+            Some(round_id) => {
+                let Some(round) = self.get_round_info_by_id(round_id) else {
+                    return EventQueryResult::Ok { events: vec![] };
+                };
+
+                from.max(round.event_offset)..round.event_offset + round.event_count
+            }
+            None => from..self.event_bus.len(),
+        };
+
+        self.event_bus.query_filtered_event(&filter, range, count)
+    }
+
+    /// Queries events emitted during `round_id`, `from` being relative to the start of the round.
+    pub fn query_round_events(&self, round_id: RoundId, from: u32, count: u32) -> EventQueryResult {
+        let Some(round) = self.get_round_info_by_id(round_id) else {
+            return EventQueryResult::Ok { events: vec![] };
+        };
+
+        let from = from.min(round.event_count);
+        let count = count.min(round.event_count - from);
+
+        self.event_bus.query_event(round.event_offset + from, count)
+    }
+
+    pub fn get_user_profile(&self, account: AccountId) -> Option<UserProfile<'_>> {
         self.user_manager.get_user_profile(&account)
     }
 
-    pub fn get_own_profile(&self) -> UserProfile {
-        let account = near_sdk::env::signer_account_id();
+    pub fn get_own_profile(&self) -> Option<UserProfile<'_>> {
+        let account = near_sdk::env::predecessor_account_id();
 
         self.user_manager.get_user_profile(&account)
     }
 
+    pub fn count_users(&self) -> u32 {
+        self.user_manager.count_users()
+    }
+
+    /// Accounts with a profile, in the order they joined.
+    pub fn list_users(&self, from: u32, limit: u32) -> Vec<AccountId> {
+        self.user_manager.list_users(from, limit)
+    }
+
+    /// Accounts whose properties satisfy all requirements, e.g. to look for workers
+    /// able to take a task before publishing it. Pages through the same directory as
+    /// `list_users`, only the matches among its `limit` entries from `from` are returned.
+    pub fn find_users_matching(
+        &self,
+        requirements: Vec<TaskRequirement>,
+        from: u32,
+        limit: u32,
+    ) -> Vec<AccountId> {
+        self.user_manager.find_users_matching(&requirements, from, limit)
+    }
+
+    /// Sets the HTTP endpoint of the caller, kept for backward compatibility.
+    /// The attached deposit has to pay for the storage the profile grows by.
+    #[payable]
     pub fn set_user_endpoint(&mut self, endpoint: String) {
-        let account = near_sdk::env::signer_account_id();
+        let account = near_sdk::env::predecessor_account_id();
+        let storage_usage = near_sdk::env::storage_usage();
 
-        self.user_manager
-            .set_user_endpoint(&account, endpoint.clone());
-        self.event_bus.emit(Events::UserProfileFieldUpdateEvent {
-            user_id: account,
-            field: "endpoint".to_string(),
-            value: endpoint,
-        })
+        self.set_endpoint(account.clone(), EndpointProtocol::Http, endpoint);
+        self.charge_storage(account, storage_usage);
     }
 
+    /// HTTP endpoint of the account, kept for backward compatibility.
     pub fn get_user_endpoint(&self, account_id: AccountId) -> Option<String> {
         self.user_manager
-            .get_user_endpoint(&account_id)
+            .get_user_endpoint(&account_id, EndpointProtocol::Http)
             .map(|e| e.to_string())
     }
 
+    /// Sets endpoints of the caller by protocol, e.g. its gRPC transcoder port and IPFS
+    /// peer address. Endpoints of other protocols are left as they are. The attached deposit
+    /// has to pay for the storage the profile grows by.
+    #[payable]
+    pub fn set_user_endpoints(&mut self, endpoints: BTreeMap<EndpointProtocol, String>) {
+        let account = near_sdk::env::predecessor_account_id();
+        let storage_usage = near_sdk::env::storage_usage();
+
+        for (protocol, url) in endpoints {
+            self.set_endpoint(account.clone(), protocol, url);
+        }
+
+        self.charge_storage(account, storage_usage);
+    }
+
+    pub fn remove_user_endpoint(&mut self, protocol: EndpointProtocol) {
+        let account = near_sdk::env::predecessor_account_id();
+
+        if self.user_manager.remove_user_endpoint(&account, protocol).is_some() {
+            self.event_bus.emit(Events::UserProfileFieldUpdateEvent {
+                user_id: account,
+                field: format!("endpoint.{protocol}"),
+                value: String::new(),
+            });
+        }
+    }
+
+    pub fn get_user_endpoints(&self, account_id: AccountId) -> BTreeMap<EndpointProtocol, String> {
+        self.user_manager
+            .get_user_endpoints(&account_id)
+            .into_iter()
+            .map(|(protocol, url)| (protocol, url.to_string()))
+            .collect()
+    }
+
+    /// Declares capabilities of the caller that task requirements are matched against,
+    /// such as `cpu_cores`, `h265_fps` or `bandwidth_mbps`. Built-in properties like
+    /// `stake` and `reputation` are maintained by the contract and cannot be set.
+    /// The attached deposit has to pay for the storage the profile grows by.
+    #[payable]
+    pub fn set_user_props(&mut self, props: Vec<(String, U256)>) {
+        let account = near_sdk::env::predecessor_account_id();
+        let storage_usage = near_sdk::env::storage_usage();
+
+        self.user_manager.set_user_props(&account, &props);
+
+        for (name, value) in props {
+            self.event_bus.emit(Events::UserProfileFieldUpdateEvent {
+                user_id: account.clone(),
+                field: name,
+                value: value.to_string(),
+            });
+        }
+
+        self.charge_storage(account, storage_usage);
+    }
+
+    pub fn remove_user_prop(&mut self, name: String) {
+        let account = near_sdk::env::predecessor_account_id();
+
+        if self.user_manager.remove_user_prop(&account, &name).is_some() {
+            self.event_bus.emit(Events::UserProfileFieldUpdateEvent {
+                user_id: account,
+                field: name,
+                value: String::new(),
+            });
+        }
+    }
+
+    #[payable]
+    pub fn stake(&mut self) -> U256 {
+        let account = near_sdk::env::predecessor_account_id();
+        let amount = near_sdk::env::attached_deposit();
+
+        ensure!(!amount.is_zero(), PocoError::InsufficientDeposit);
+
+        let stake = self.user_manager.stake(&account, amount);
+
+        self.emit_stake_update(account, StakeAction::Stake, amount, stake)
+    }
+
+    /// Starts unbonding `amount` of the caller's stake. It can be withdrawn
+    /// once the configured number of unbonding rounds have passed.
+    pub fn unstake(&mut self, amount: U256) -> U256 {
+        let account = near_sdk::env::predecessor_account_id();
+
+        // No stake can exceed the NEAR supply, so larger amounts are never covered.
+        ensure!(amount <= U256::from(u128::MAX), PocoError::InsufficientStake);
+
+        let amount = NearToken::from_yoctonear(amount.as_u128());
+        let unlock_round = self.get_round_id() + self.config.unbonding_rounds;
+
+        let stake = self.user_manager.unstake(&account, amount, unlock_round);
+
+        self.emit_stake_update(account, StakeAction::Unstake, amount, stake)
+    }
+
+    pub fn withdraw_unstaked(&mut self) -> U256 {
+        let account = near_sdk::env::predecessor_account_id();
+        let current_round_id = self.get_round_id();
+        let amount = self
+            .user_manager
+            .withdraw_unstaked(&account, current_round_id);
+        let stake = self.user_manager.get_user_stake(&account);
+
+        self.transfer(account.clone(), amount);
+
+        self.emit_stake_update(account, StakeAction::Withdraw, amount, stake)
+    }
+
+    pub fn get_user_stake(&self, account_id: AccountId) -> U256 {
+        U256::from(self.user_manager.get_user_stake(&account_id).as_yoctonear())
+    }
+
+    pub fn get_unbonding_stake(&self, account_id: AccountId) -> Option<UnbondingStake> {
+        self.user_manager.get_unbonding_stake(&account_id).cloned()
+    }
+
+    /// Publishes a task. The attached deposit must cover the largest offered bounty for each
+    /// replica and is held in escrow until the task is settled, expired or cancelled.
+    #[payable]
     pub fn publish_task(&mut self, config: TaskConfig) -> TaskId {
-        assert_eq!(
-            self.get_round_status(),
-            RoundStatus::Running,
-            "Round has not been started yet."
+        self.assert_can_publish();
+        self.assert_valid_task_config(&config);
+
+        let deposit = near_sdk::env::attached_deposit();
+
+        ensure!(
+            U256::from(deposit.as_yoctonear()) >= config.required_escrow(),
+            PocoError::InsufficientDeposit
         );
 
-        let owner = near_sdk::env::signer_account_id();
-        let current_round_id = self.get_round_id();
+        let stakers = self.assignment_candidates();
 
-        let (task_id, config) =
-            self.task_manager
-                .publish_task(current_round_id, owner, config.clone());
+        self.publish(config, deposit, &stakers)
+    }
 
-        self.event_bus.emit(Events::NewTaskEvent {
-            task_id: task_id.clone(),
-            task_config: config,
-        });
+    /// Publishes several tasks at once, they get consecutive ids in the current round.
+    /// The attached deposit must cover the sum of their required escrows, each task escrows
+    /// its own and the rest of the deposit is refunded.
+    #[payable]
+    pub fn publish_tasks(&mut self, configs: Vec<TaskConfig>) -> Vec<TaskId> {
+        self.assert_can_publish();
 
-        task_id
+        let bounties: Vec<U256> = configs
+            .iter()
+            .map(|config| {
+                self.assert_valid_task_config(config);
+
+                config.required_escrow()
+            })
+            .collect();
+
+        let deposit = near_sdk::env::attached_deposit();
+        let total_bounty = bounties
+            .iter()
+            .fold(U256::zero(), |total, bounty| total.saturating_add(*bounty));
+
+        ensure!(
+            U256::from(deposit.as_yoctonear()) >= total_bounty,
+            PocoError::InsufficientDeposit
+        );
+
+        let stakers = self.assignment_candidates();
+
+        let task_ids = configs
+            .into_iter()
+            .zip(bounties)
+            .map(|(config, bounty)| {
+                self.publish(config, NearToken::from_yoctonear(bounty.as_u128()), &stakers)
+            })
+            .collect();
+
+        self.transfer(
+            near_sdk::env::predecessor_account_id(),
+            deposit.saturating_sub(NearToken::from_yoctonear(total_bounty.as_u128())),
+        );
+
+        task_ids
     }
 
     pub fn count_tasks(&self) -> u64 {
         self.task_manager.len()
     }
-}
 
-#[cfg(test)]
-mod tests {}
+    pub fn get_task_status(&self, task_id: TaskId) -> Option<TaskStatus> {
+        self.task_manager.get_task_status(&task_id)
+    }
+
+    /// Claims a published task for the caller. Every task requirement must hold for the
+    /// claimer's profile, and the claimer must qualify for at least one offer; the one
+    /// with the largest bounty is recorded on the attempt.
+    pub fn claim_task(&mut self, task_id: TaskId) {
+        self.assert_not_paused();
+
+        if self.task_manager.is_claim_timed_out(&task_id) {
+            self.release_timed_out_claim(&task_id);
+        }
+
+        ensure!(
+            !self.task_manager.is_claim_deadline_passed(&task_id),
+            PocoError::ClaimDeadlinePassed
+        );
+
+        let worker = near_sdk::env::predecessor_account_id();
+        let task = self
+            .task_manager
+            .get_task(&task_id)
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic());
+
+        ensure!(
+            task_id.get_round_id() == self.get_round_id()
+                && self.get_round_status() == RoundStatus::Running,
+            PocoError::RoundNotRunning
+        );
+        ensure!(task.owner != worker, PocoError::OwnerCannotClaim);
+        ensure!(
+            self.assignment_manager.is_assigned_worker(&task_id, &worker),
+            PocoError::NotAssigned
+        );
+        ensure!(
+            U256::from(self.user_manager.get_user_stake(&worker).as_yoctonear())
+                >= self.config.min_stake,
+            PocoError::InsufficientStake
+        );
+        ensure!(
+            self.user_manager
+                .meets_requirements(&worker, &task.requirements),
+            PocoError::RequirementUnmet
+        );
+
+        let offer_index = task
+            .offer
+            .iter()
+            .enumerate()
+            .filter(|(_, offer)| {
+                self.user_manager
+                    .meets_requirements(&worker, offer.requirements.as_deref().unwrap_or(&[]))
+            })
+            .max_by_key(|(_, offer)| offer.bounty)
+            .map(|(index, _)| index as u32);
+
+        ensure!(
+            task.offer.is_empty() || offer_index.is_some(),
+            PocoError::RequirementUnmet
+        );
+
+        let claimed_before =
+            self.task_manager.get_task_status(&task_id) == Some(TaskStatus::Claimed);
+
+        self.task_manager.claim_task(&task_id, worker, offer_index);
+
+        if !claimed_before {
+            self.event_bus.emit(Events::TaskStatusUpdateEvent {
+                task_id,
+                status: TaskStatus::Claimed,
+            });
+        }
+    }
+
+    /// Submits the result of a claimed task. `output` references the produced output
+    /// according to the task's output source, e.g. an IPFS CID. A replicated task is
+    /// verified as soon as the digests of a quorum of its results agree.
+    pub fn submit_task_result(&mut self, task_id: TaskId, output: String, digest: String) {
+        let worker = near_sdk::env::predecessor_account_id();
+        let task = self
+            .task_manager
+            .get_task(&task_id)
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic());
+
+        if let Err(error) = task.output.validate_result(&output, &digest) {
+            error.panic();
+        }
+
+        let replicas = task.replicas;
+
+        let result = self
+            .task_manager
+            .submit_task_result(&task_id, worker, output, digest);
+
+        self.event_bus.emit(Events::TaskResultSubmittedEvent {
+            task_id: task_id.clone(),
+            result,
+        });
+
+        if replicas > 1 {
+            self.resolve_quorum(&task_id);
+        } else {
+            self.event_bus.emit(Events::TaskStatusUpdateEvent {
+                task_id,
+                status: TaskStatus::Submitted,
+            });
+        }
+    }
+
+    /// Publishes a claimed task again once its execution timeout has passed,
+    /// so that another worker can pick it up.
+    pub fn release_task_claim(&mut self, task_id: TaskId) {
+        ensure!(
+            self.task_manager.is_claim_timed_out(&task_id),
+            PocoError::ClaimNotTimedOut
+        );
+
+        self.release_timed_out_claim(&task_id);
+    }
+
+    pub fn get_claim_timeout(&self, task_id: TaskId) -> Option<BlockTimestamp> {
+        self.task_manager.get_claim_timeout(&task_id)
+    }
+
+    pub fn get_task_results(&self, task_id: TaskId) -> Vec<TaskResult> {
+        self.task_manager.get_task_results(&task_id)
+    }
+
+    pub fn get_assignments(&self, task_id: TaskId) -> Option<TaskAssignment> {
+        self.assignment_manager.get_assignment(&task_id).cloned()
+    }
+
+    pub fn verify_task(&mut self, task_id: TaskId) {
+        self.assert_task_verifier(&task_id);
+
+        self.accept(&task_id);
+    }
+
+    /// Accepts a submitted result nobody verified or rejected within the verification
+    /// timeout, so that a task cannot be held in `Submitted` by an unresponsive owner.
+    pub fn accept_task(&mut self, task_id: TaskId) {
+        ensure!(
+            self.is_verification_expired(&task_id),
+            PocoError::VerificationNotExpired
+        );
+
+        self.accept(&task_id);
+    }
+
+    /// Rejects a submitted result. Unlike verifying, this is left to the task's verifiers.
+    pub fn reject_task(&mut self, task_id: TaskId) {
+        self.assert_committee_verifier(&task_id);
+
+        self.transit_task(&task_id, TaskStatus::Rejected);
+        self.record_rewarded_outcomes(&task_id, false);
+    }
+
+    /// Pays the bounty of the verified attempt's offer to its worker and refunds
+    /// the rest of the escrow to the task owner, once the challenge window has closed.
+    pub fn settle_task(&mut self, task_id: TaskId) {
+        ensure!(
+            !self.is_challenge_window_open(&task_id),
+            PocoError::ChallengeWindowOpen
+        );
+
+        self.settle(&task_id);
+    }
+
+    /// Disputes the accepted result of a task within its challenge window, claiming the
+    /// output should have `counter_digest`. The attached deposit is the challenger's bond:
+    /// it goes to the workers of the result if the verifiers reproduce it, otherwise it is
+    /// refunded and each worker is slashed by the configured dispute slash in favour of the
+    /// challenger. Only tasks with enough drawn verifiers to vote on it can be disputed.
+    #[payable]
+    pub fn dispute_result(&mut self, task_id: TaskId, counter_digest: String) {
+        self.assert_not_paused();
+
+        let challenger = near_sdk::env::predecessor_account_id();
+        let bond = U256::from(near_sdk::env::attached_deposit().as_yoctonear());
+        let stake = U256::from(self.user_manager.get_user_stake(&challenger).as_yoctonear());
+        let prior_status = self
+            .task_manager
+            .get_task_status(&task_id)
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic());
+
+        ensure!(
+            !bond.is_zero() && bond >= self.config.min_dispute_bond,
+            PocoError::InsufficientDeposit
+        );
+        ensure!(
+            !stake.is_zero() && stake >= self.config.min_stake,
+            PocoError::InsufficientStake
+        );
+        ensure!(
+            self.dispute_manager.get_open_dispute(&task_id).is_none(),
+            PocoError::DisputeAlreadyOpen
+        );
+        ensure!(
+            self.is_challenge_window_open(&task_id),
+            PocoError::ChallengeWindowClosed
+        );
+
+        let committee = self
+            .assignment_manager
+            .get_assignment(&task_id)
+            .map(|assignment| assignment.verifiers.len())
+            .unwrap_or_default();
+
+        ensure!(
+            committee >= self.config.min_dispute_committee.max(1) as usize,
+            PocoError::DisputeCommitteeTooSmall
+        );
+
+        let task = self.task_manager.get_task(&task_id).unwrap();
+        let attempts = self.task_manager.get_rewarded_attempts(&task_id);
+        let defendants: Vec<AccountId> = attempts
+            .iter()
+            .map(|attempt| attempt.worker.clone())
+            .collect();
+        let disputed_digest = attempts
+            .iter()
+            .find_map(|attempt| attempt.result.as_ref())
+            .map(|result| result.digest.clone())
+            .unwrap_or_else(|| PocoError::NoOpenAttempt.panic());
+
+        ensure!(
+            task.owner != challenger && !defendants.contains(&challenger),
+            PocoError::ConflictOfInterest
+        );
+
+        self.transit_task(&task_id, TaskStatus::Disputed);
+        self.dispute_manager.open(
+            &task_id,
+            Dispute {
+                challenger: challenger.clone(),
+                counter_digest: counter_digest.clone(),
+                bond,
+                defendants,
+                disputed_digest,
+                prior_status,
+                opened_at: near_sdk::env::block_timestamp_ms().into(),
+                votes: Vec::new(),
+                status: DisputeStatus::Open,
+            },
+        );
+
+        self.event_bus.emit(Events::DisputeOpenedEvent {
+            task_id,
+            challenger,
+            counter_digest,
+            bond,
+        });
+    }
+
+    /// Votes on the open dispute of a task with the digest the verifier got by executing
+    /// the task again. Only the verifiers drawn for the task vote, the owner cannot stand in
+    /// for them, and the dispute is resolved once a majority of them agree.
+    pub fn vote_on_dispute(&mut self, task_id: TaskId, digest: String) {
+        let verifier = near_sdk::env::predecessor_account_id();
+
+        ensure!(
+            self.role_manager.holds_role(&verifier, Role::Verifier),
+            PocoError::Unauthorized
+        );
+
+        let committee = self
+            .assignment_manager
+            .get_assignment(&task_id)
+            .map(|assignment| assignment.verifiers.clone())
+            .unwrap_or_default();
+
+        ensure!(committee.contains(&verifier), PocoError::NotAssigned);
+
+        let dispute = self
+            .dispute_manager
+            .get_open_dispute(&task_id)
+            .unwrap_or_else(|| PocoError::NoOpenDispute.panic());
+
+        ensure!(
+            dispute.challenger != verifier && !dispute.defendants.contains(&verifier),
+            PocoError::ConflictOfInterest
+        );
+        ensure!(!self.is_dispute_expired(&task_id), PocoError::DisputeExpired);
+
+        let threshold = committee.len() / 2 + 1;
+
+        if let Some(digest) = self.dispute_manager.vote(&task_id, verifier, digest, threshold) {
+            self.resolve_dispute(&task_id, digest);
+        }
+    }
+
+    /// Closes a dispute the verifiers did not resolve before the dispute timeout. The bond
+    /// is refunded to the challenger and the task gets back the status it had before.
+    pub fn expire_dispute(&mut self, task_id: TaskId) {
+        ensure!(
+            self.dispute_manager.get_open_dispute(&task_id).is_some(),
+            PocoError::NoOpenDispute
+        );
+        ensure!(self.is_dispute_expired(&task_id), PocoError::DisputeNotExpired);
+
+        self.expire_open_dispute(&task_id);
+    }
+
+    pub fn get_dispute_deadline(&self, task_id: TaskId) -> Option<BlockTimestamp> {
+        self.dispute_manager
+            .get_open_dispute(&task_id)
+            .map(|dispute| dispute.opened_at + self.config.dispute_timeout)
+    }
+
+    pub fn get_dispute(&self, task_id: TaskId) -> Option<Dispute> {
+        self.dispute_manager.get_dispute(&task_id).cloned()
+    }
+
+    pub fn get_challenge_deadline(&self, task_id: TaskId) -> Option<BlockTimestamp> {
+        self.task_manager
+            .get_challenge_deadline(&task_id, self.config.challenge_window)
+    }
+
+    /// Withdraws a task nobody has claimed yet and refunds its escrow to the owner.
+    pub fn cancel_task(&mut self, task_id: TaskId) {
+        self.assert_not_paused();
+        self.assert_task_owner(&task_id);
+
+        self.transit_task(&task_id, TaskStatus::Cancelled);
+        self.refund_escrow(&task_id);
+
+        self.event_bus.emit(Events::TaskCancelledEvent { task_id });
+    }
+
+    /// Replaces the offers of an unclaimed task. The task with its new offers is validated
+    /// like a published config, the largest bounty may only be raised, and the attached
+    /// deposit is added to the escrow, which has to cover the new largest bounty for each
+    /// replica.
+    #[payable]
+    pub fn update_task_offer(&mut self, task_id: TaskId, offers: Vec<TaskOffer>) {
+        self.assert_not_paused();
+        self.assert_task_owner(&task_id);
+
+        ensure!(
+            self.task_manager.get_task_status(&task_id) == Some(TaskStatus::Published),
+            PocoError::TaskAlreadyClaimed
+        );
+
+        let task = self.task_manager.get_task(&task_id).unwrap().clone();
+        let max_bounty = Self::max_bounty(&task.offer);
+        let config = TaskConfig {
+            offer: offers.clone(),
+            ..task.to_task_config()
+        };
+
+        if let Err(error) = config.validate(self.config.max_config_bytes) {
+            error.panic();
+        }
+
+        ensure!(
+            Self::max_bounty(&offers) >= max_bounty,
+            PocoError::BountyDecreased
+        );
+
+        self.task_manager
+            .deposit_escrow(&task_id, near_sdk::env::attached_deposit());
+
+        ensure!(
+            U256::from(self.task_manager.get_escrow(&task_id).as_yoctonear())
+                >= config.required_escrow(),
+            PocoError::InsufficientDeposit
+        );
+
+        self.task_manager.update_task_offer(&task_id, offers.clone());
+
+        self.event_bus.emit(Events::TaskOfferUpdatedEvent {
+            task_id,
+            offer: offers,
+        });
+    }
+
+    /// Expires a task whose round has ended, or an unclaimed task past its claim deadline.
+    /// A claimed task only expires once its execution timeout has passed.
+    pub fn expire_task(&mut self, task_id: TaskId) {
+        let round_id = task_id.get_round_id();
+
+        let round_ended = round_id != self.get_round_id() || !self.round_manager.is_round_running();
+        let unclaimed_past_deadline = self.task_manager.is_claim_deadline_passed(&task_id)
+            && matches!(
+                self.task_manager.get_task_status(&task_id),
+                Some(TaskStatus::Published | TaskStatus::Rejected)
+            );
+
+        ensure!(
+            round_ended || unclaimed_past_deadline,
+            PocoError::RoundStillRunning
+        );
+        ensure!(
+            self.task_manager.get_task_status(&task_id) != Some(TaskStatus::Claimed)
+                || self.is_claim_expired(&task_id),
+            PocoError::ClaimNotTimedOut
+        );
+
+        self.expire(&task_id);
+    }
+
+    pub fn get_reputation(&self, account_id: AccountId) -> Reputation {
+        self.user_manager.get_reputation(&account_id)
+    }
+}
+
+impl Contract {
+    /// Checks the role of the predecessor rather than the signer, so that a contract the
+    /// owner's transaction passes through cannot call back with the owner's rights.
+    fn assert_role(&self, role: Role) {
+        let account = near_sdk::env::predecessor_account_id();
+
+        if !self.role_manager.has_role(&account, role) {
+            PocoError::Unauthorized.panic_with(format_args!("requires role {role}"));
+        }
+    }
+
+    fn assert_not_paused(&self) {
+        ensure!(!self.paused, PocoError::ContractPaused);
+    }
+
+    /// The task owner and accounts granted the verifier role may verify a task, verifiers
+    /// only if they were drawn for it. A disputed task is left to the vote on the dispute.
+    /// The owner of a task may verify it, as may any verifier of its committee.
+    fn assert_task_verifier(&self, task_id: &TaskId) {
+        let task = self
+            .task_manager
+            .get_task(task_id)
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic());
+
+        if task.owner == near_sdk::env::predecessor_account_id() {
+            ensure!(
+                self.dispute_manager.get_open_dispute(task_id).is_none(),
+                PocoError::DisputeAlreadyOpen
+            );
+
+            return;
+        }
+
+        self.assert_committee_verifier(task_id);
+    }
+
+    /// Only verifiers drawn for the task, or any verifier when none was drawn, may reject it.
+    /// The owner is left out, since a rejection lets it expire the task and take its escrow back.
+    fn assert_committee_verifier(&self, task_id: &TaskId) {
+        let account = near_sdk::env::predecessor_account_id();
+
+        ensure!(
+            self.dispute_manager.get_open_dispute(task_id).is_none(),
+            PocoError::DisputeAlreadyOpen
+        );
+        let task = self
+            .task_manager
+            .get_task(task_id)
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic());
+
+        ensure!(task.owner != account, PocoError::ConflictOfInterest);
+        ensure!(
+            self.role_manager.holds_role(&account, Role::Verifier),
+            PocoError::Unauthorized
+        );
+        ensure!(
+            self.assignment_manager.is_assigned_verifier(task_id, &account),
+            PocoError::NotAssigned
+        );
+    }
+
+    fn assert_can_publish(&self) {
+        self.assert_not_paused();
+
+        ensure!(
+            self.get_round_status() == RoundStatus::Running,
+            PocoError::RoundNotRunning
+        );
+    }
+
+    fn assert_valid_task_config(&self, config: &TaskConfig) {
+        if let Err(error) = config.validate(self.config.max_config_bytes) {
+            error.panic();
+        }
+
+        if !self.task_types.is_registered(&config.r#type) {
+            PocoError::UnknownTaskType.panic_with(&config.r#type);
+        }
+    }
+
+    fn max_bounty(offers: &[TaskOffer]) -> U256 {
+        offers
+            .iter()
+            .map(|offer| offer.bounty)
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn publish(
+        &mut self,
+        config: TaskConfig,
+        escrow: NearToken,
+        stakers: &[(AccountId, u128)],
+    ) -> TaskId {
+        let owner = near_sdk::env::predecessor_account_id();
+        let current_round_id = self.get_round_id();
+
+        let (task_id, config) = self
+            .task_manager
+            .publish_task(current_round_id, owner, config);
+        self.task_manager.deposit_escrow(&task_id, escrow);
+
+        self.event_bus.emit(Events::NewTaskEvent {
+            task_id: task_id.clone(),
+            task_config: config.clone(),
+        });
+
+        self.assign(&task_id, &config, stakers);
+
+        task_id
+    }
+
+    /// Candidates of the assignment pool staking at least the minimum stake, with their stake.
+    /// Collected once per publish call and shared by the draws of all the tasks it publishes.
+    fn assignment_candidates(&self) -> Vec<(AccountId, u128)> {
+        if self.config.assigned_workers == 0 && self.config.assigned_verifiers == 0 {
+            return Vec::new();
+        }
+
+        self.assignment_manager
+            .get_candidates()
+            .filter(|(_, stake)| U256::from(**stake) >= self.config.min_stake)
+            .map(|(account, stake)| (account.clone(), *stake))
+            .collect()
+    }
+
+    /// Draws the workers and verifiers of a new task among the stakers, weighted by stake.
+    /// The seed is derived from the block's random seed and the task id, and is recorded
+    /// with the assignment so the draw can be audited.
+    fn assign(
+        &mut self,
+        task_id: &TaskId,
+        config: &OnChainTaskConfig,
+        stakers: &[(AccountId, u128)],
+    ) {
+        if self.config.assigned_workers == 0 && self.config.assigned_verifiers == 0 {
+            return;
+        }
+
+        let seed = near_sdk::env::sha256_array(
+            &[
+                near_sdk::env::random_seed_array().as_slice(),
+                &u64::from(task_id).to_le_bytes(),
+            ]
+            .concat(),
+        );
+
+        let stakers: Vec<&(AccountId, u128)> = stakers
+            .iter()
+            .filter(|(account, _)| *account != config.owner)
+            .collect();
+
+        let workers = AssignmentManager::draw(
+            seed,
+            stakers
+                .iter()
+                .filter(|(account, _)| {
+                    self.user_manager
+                        .meets_requirements(account, &config.requirements)
+                })
+                .map(|staker| (*staker).clone())
+                .collect(),
+            match self.config.assigned_workers {
+                0 => 0,
+                assigned_workers => assigned_workers.max(config.replicas),
+            },
+        );
+        let verifiers = AssignmentManager::draw(
+            near_sdk::env::sha256_array(&seed),
+            stakers
+                .into_iter()
+                .filter(|(account, _)| {
+                    !workers.contains(account)
+                        && self.role_manager.holds_role(account, Role::Verifier)
+                })
+                .cloned()
+                .collect(),
+            self.config.assigned_verifiers,
+        );
+
+        let assignment = TaskAssignment {
+            workers,
+            verifiers,
+            seed,
+            assigned_at: near_sdk::env::block_timestamp_ms().into(),
+        };
+
+        self.assignment_manager.assign(task_id, assignment.clone());
+
+        self.event_bus.emit(Events::TaskAssignedEvent {
+            task_id: task_id.clone(),
+            assignment,
+        });
+    }
+
+    fn assert_task_owner(&self, task_id: &TaskId) {
+        let task = self
+            .task_manager
+            .get_task(task_id)
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic());
+
+        ensure!(
+            task.owner == near_sdk::env::predecessor_account_id(),
+            PocoError::Unauthorized
+        );
+    }
+
+    fn set_endpoint(&mut self, account: AccountId, protocol: EndpointProtocol, url: String) {
+        self.user_manager
+            .set_user_endpoint(&account, protocol, url.clone());
+
+        self.event_bus.emit(Events::UserProfileFieldUpdateEvent {
+            user_id: account,
+            field: format!("endpoint.{protocol}"),
+            value: url,
+        });
+    }
+
+    /// Charges the storage used since `initial_usage` to the attached deposit, refunding the
+    /// rest to the account. Storage freed in the meantime is not paid back.
+    fn charge_storage(&mut self, account: AccountId, initial_usage: StorageUsage) {
+        self.user_manager.flush();
+
+        let used = near_sdk::env::storage_usage().saturating_sub(initial_usage);
+        let cost = near_sdk::env::storage_byte_cost().saturating_mul(used.into());
+        let deposit = near_sdk::env::attached_deposit();
+
+        ensure!(deposit >= cost, PocoError::InsufficientDeposit);
+
+        self.transfer(account, deposit.saturating_sub(cost));
+    }
+
+    fn transfer(&self, account: AccountId, amount: NearToken) {
+        if !amount.is_zero() {
+            Promise::new(account).transfer(amount);
+        }
+    }
+
+    fn emit_stake_update(
+        &mut self,
+        account: AccountId,
+        action: StakeAction,
+        amount: NearToken,
+        stake: NearToken,
+    ) -> U256 {
+        self.assignment_manager
+            .update_candidate(&account, stake.as_yoctonear());
+
+        let stake = U256::from(stake.as_yoctonear());
+
+        self.event_bus.emit(Events::StakeUpdateEvent {
+            user_id: account,
+            action,
+            amount: U256::from(amount.as_yoctonear()),
+            stake,
+        });
+
+        stake
+    }
+
+    /// Verifies a replicated task once a quorum of its results agree, flagging and slashing
+    /// the workers that disagreed, or rejects it once every replica reported without agreement.
+    fn resolve_quorum(&mut self, task_id: &TaskId) {
+        match self.task_manager.check_quorum(task_id) {
+            QuorumOutcome::Pending => {}
+            QuorumOutcome::Reached { digest, dissenters } => {
+                self.transit_task(task_id, TaskStatus::Submitted);
+                self.transit_task(task_id, TaskStatus::Verified);
+
+                let mut flagged = Vec::with_capacity(dissenters.len());
+
+                for (worker, digest) in dissenters {
+                    flagged.push(worker.clone());
+
+                    self.event_bus.emit(Events::WorkerFlaggedEvent {
+                        task_id: task_id.clone(),
+                        worker,
+                        digest,
+                    });
+                }
+
+                self.task_manager
+                    .record_consensus(task_id, digest, flagged.clone());
+                self.record_rewarded_outcomes(task_id, true);
+
+                let owner = self.task_manager.get_task(task_id).unwrap().owner.clone();
+                let dissent_slash = NearToken::from_yoctonear(self.config.dissent_slash.as_u128());
+                let mut compensation = NearToken::from_yoctonear(0);
+
+                for worker in flagged {
+                    self.record_outcome(task_id, worker.clone(), false);
+
+                    if !dissent_slash.is_zero() {
+                        let (slashed, stake) = self.user_manager.slash(&worker, dissent_slash);
+
+                        compensation = compensation.saturating_add(slashed);
+                        self.emit_stake_update(worker, StakeAction::Slash, slashed, stake);
+                    }
+                }
+
+                // The stake slashed from dissenting workers compensates the task owner.
+                self.transfer(owner, compensation);
+            }
+            QuorumOutcome::Failed => {
+                self.transit_task(task_id, TaskStatus::Submitted);
+                self.transit_task(task_id, TaskStatus::Rejected);
+
+                for result in self.task_manager.get_task_results(task_id) {
+                    self.record_outcome(task_id, result.worker, false);
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn is_challenge_window_open(&self, task_id: &TaskId) -> bool {
+        let now: BlockTimestamp = near_sdk::env::block_timestamp_ms().into();
+
+        self.task_manager
+            .get_challenge_deadline(task_id, self.config.challenge_window)
+            .is_some_and(|deadline| now <= deadline)
+    }
+
+    /// Upholds the dispute if the verifiers agreed on another digest than the disputed one,
+    /// rejecting the task and slashing its workers, otherwise dismisses it and restores the task.
+    fn resolve_dispute(&mut self, task_id: &TaskId, digest: String) {
+        let dispute = self.dispute_manager.get_open_dispute(task_id).cloned().unwrap();
+        let bond = NearToken::from_yoctonear(dispute.bond.as_u128());
+        let upheld = digest != dispute.disputed_digest;
+
+        if upheld {
+            self.transit_task(task_id, TaskStatus::Rejected);
+
+            let dispute_slash = NearToken::from_yoctonear(self.config.dispute_slash.as_u128());
+            let mut payout = bond;
+
+            for defendant in dispute.defendants {
+                let (slashed, stake) = self.user_manager.slash(&defendant, dispute_slash);
+
+                payout = payout.saturating_add(slashed);
+                self.record_outcome(task_id, defendant.clone(), false);
+                self.emit_stake_update(defendant, StakeAction::Slash, slashed, stake);
+            }
+
+            self.transfer(dispute.challenger, payout);
+        } else {
+            self.transit_task(task_id, dispute.prior_status);
+
+            let defendants = dispute.defendants.len().max(1) as u128;
+            let share = NearToken::from_yoctonear(bond.as_yoctonear() / defendants);
+            let mut remainder = bond;
+
+            for defendant in dispute.defendants {
+                remainder = remainder.saturating_sub(share);
+                self.transfer(defendant, share);
+            }
+
+            // Whatever does not split evenly goes back to the challenger.
+            self.transfer(dispute.challenger.clone(), remainder);
+            self.record_outcome(task_id, dispute.challenger, false);
+        }
+
+        let status = if upheld {
+            DisputeStatus::Upheld
+        } else {
+            DisputeStatus::Dismissed
+        };
+
+        self.dispute_manager.close(task_id, status);
+
+        self.event_bus.emit(Events::DisputeResolvedEvent {
+            task_id: task_id.clone(),
+            status,
+            digest,
+        });
+    }
+
+    fn is_dispute_expired(&self, task_id: &TaskId) -> bool {
+        let now: BlockTimestamp = near_sdk::env::block_timestamp_ms().into();
+
+        self.get_dispute_deadline(task_id.clone())
+            .is_some_and(|deadline| now > deadline)
+    }
+
+    fn expire_open_dispute(&mut self, task_id: &TaskId) {
+        let dispute = self.dispute_manager.close(task_id, DisputeStatus::Expired);
+
+        self.transit_task(task_id, dispute.prior_status);
+        self.transfer(
+            dispute.challenger,
+            NearToken::from_yoctonear(dispute.bond.as_u128()),
+        );
+
+        self.event_bus.emit(Events::DisputeResolvedEvent {
+            task_id: task_id.clone(),
+            status: DisputeStatus::Expired,
+            digest: String::new(),
+        });
+    }
+
+    /// Pays each rewarded attempt the bounty of its offer out of the escrow, and refunds
+    /// what is left to the task owner.
+    fn settle(&mut self, task_id: &TaskId) -> NearToken {
+        self.transit_task(task_id, TaskStatus::Settled);
+
+        let task = self.task_manager.get_task(task_id).unwrap();
+        let owner = task.owner.clone();
+        let rewards: Vec<(AccountId, NearToken)> = self
+            .task_manager
+            .get_rewarded_attempts(task_id)
+            .into_iter()
+            .map(|attempt| {
+                let bounty = attempt
+                    .offer_index
+                    .and_then(|index| task.offer.get(index as usize))
+                    .map(|offer| NearToken::from_yoctonear(offer.bounty.as_u128()))
+                    .unwrap_or(NearToken::from_yoctonear(0));
+
+                (attempt.worker.clone(), bounty)
+            })
+            .collect();
+
+        ensure!(!rewards.is_empty(), PocoError::NoOpenAttempt);
+
+        let mut escrow = self.task_manager.take_escrow(task_id);
+        let mut bounty_paid = NearToken::from_yoctonear(0);
+
+        for (worker, bounty) in rewards {
+            let bounty = bounty.min(escrow);
+
+            escrow = escrow.saturating_sub(bounty);
+            bounty_paid = bounty_paid.saturating_add(bounty);
+            self.transfer(worker, bounty);
+        }
+
+        self.task_manager.set_bounty_paid(task_id, bounty_paid);
+        self.transfer(owner, escrow);
+
+        bounty_paid
+    }
+
+    /// Publishes a task again after its claim timed out, the workers who let it time out
+    /// are recorded as having failed it.
+    fn release_timed_out_claim(&mut self, task_id: &TaskId) {
+        for worker in self.task_manager.get_open_workers(task_id) {
+            self.record_outcome(task_id, worker, false);
+        }
+
+        self.transit_task(task_id, TaskStatus::Published);
+    }
+
+    /// Whether a submitted task is past the time its verifiers had to verify or reject it.
+    fn is_verification_expired(&self, task_id: &TaskId) -> bool {
+        let now: BlockTimestamp = near_sdk::env::block_timestamp_ms().into();
+
+        self.task_manager
+            .get_task_state(task_id)
+            .is_some_and(|state| {
+                state.status == TaskStatus::Submitted
+                    && now > state.updated_at + self.config.verification_timeout
+            })
+    }
+
+    /// Whether a claimed task is past its claim. Claims of tasks without an execution
+    /// timeout last until the end of the task's round.
+    fn is_claim_expired(&self, task_id: &TaskId) -> bool {
+        self.task_manager.get_claim_timeout(task_id).is_none()
+            || self.task_manager.is_claim_timed_out(task_id)
+    }
+
+    /// Expires the task and refunds its escrow, counting a failure for every worker
+    /// that claimed it without submitting a result.
+    fn expire(&mut self, task_id: &TaskId) {
+        if self.task_manager.get_task_status(task_id) == Some(TaskStatus::Claimed) {
+            for worker in self.task_manager.get_open_workers(task_id) {
+                self.record_outcome(task_id, worker, false);
+            }
+        }
+
+        self.transit_task(task_id, TaskStatus::Expired);
+        self.refund_escrow(task_id);
+    }
+
+    fn record_outcome(&mut self, task_id: &TaskId, account: AccountId, success: bool) {
+        let reputation = self.user_manager.record_outcome(&account, success);
+
+        self.event_bus.emit(Events::ReputationUpdateEvent {
+            user_id: account,
+            task_id: task_id.clone(),
+            reputation,
+        });
+    }
+
+    fn accept(&mut self, task_id: &TaskId) {
+        self.transit_task(task_id, TaskStatus::Verified);
+        self.record_rewarded_outcomes(task_id, true);
+    }
+
+    fn record_rewarded_outcomes(&mut self, task_id: &TaskId, success: bool) {
+        let workers: Vec<AccountId> = self
+            .task_manager
+            .get_rewarded_attempts(task_id)
+            .into_iter()
+            .map(|attempt| attempt.worker.clone())
+            .collect();
+
+        for worker in workers {
+            self.record_outcome(task_id, worker, success);
+        }
+    }
+
+    fn refund_escrow(&mut self, task_id: &TaskId) {
+        let owner = self.task_manager.get_task(task_id).unwrap().owner.clone();
+        let escrow = self.task_manager.take_escrow(task_id);
+
+        self.transfer(owner, escrow);
+    }
+
+    fn transit_task(&mut self, task_id: &TaskId, status: TaskStatus) {
+        self.task_manager.transit_task(task_id, status);
+
+        self.event_bus.emit(Events::TaskStatusUpdateEvent {
+            task_id: task_id.clone(),
+            status,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+    use poco_types::types::task::{TaskInputSource, TaskOutputSource};
+
+    use super::*;
+
+    const ROUND_DURATION_IN_MS: u64 = 60_000;
+    /// Block time the test timestamps count from, once the initial round 0 has run out.
+    const GENESIS_IN_MS: u64 = ROUND_DURATION_IN_MS;
+
+    fn set_context(signer: AccountId, deposit: u128, timestamp_in_ms: u64) {
+        set_call_context(signer.clone(), signer, deposit, timestamp_in_ms);
+    }
+
+    /// A call `predecessor` makes on behalf of a transaction `signer` signed.
+    fn set_call_context(
+        signer: AccountId,
+        predecessor: AccountId,
+        deposit: u128,
+        timestamp_in_ms: u64,
+    ) {
+        testing_env!(VMContextBuilder::new()
+            .signer_account_id(signer)
+            .predecessor_account_id(predecessor)
+            .attached_deposit(NearToken::from_yoctonear(deposit))
+            .block_timestamp((GENESIS_IN_MS + timestamp_in_ms) * 1_000_000)
+            .build());
+    }
+
+    /// A contract owned by `accounts(0)` with its first round started at 1s.
+    fn setup() -> Contract {
+        set_context(accounts(0), 0, 1_000);
+
+        let mut contract = Contract::new(
+            accounts(0),
+            RoundDuration::new(ROUND_DURATION_IN_MS),
+            None,
+            None,
+            None,
+            None,
+            Some(vec![TaskType {
+                name: "MEDIA_TRANSCODING".to_string(),
+                version: 1,
+                description: "Media transcoding tasks".to_string(),
+                schema_cid: "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+            }]),
+        );
+        contract.start_new_round();
+
+        contract
+    }
+
+    fn task_config(bounty: u64) -> TaskConfig {
+        TaskConfig {
+            input: TaskInputSource::Link {
+                url: "https://example.com/input.mp4".to_string(),
+            },
+            output: TaskOutputSource::Ipfs,
+            requirements: vec![],
+            offer: vec![TaskOffer {
+                bounty: U256::from(bounty),
+                requirements: None,
+            }],
+            config: vec![],
+            r#type: "MEDIA_TRANSCODING".to_string(),
+            claim_deadline: None,
+            execution_timeout: None,
+            replicas: 1,
+            quorum: 1,
+        }
+    }
+
+    /// Publishes a task from `accounts(1)` with `deposit` attached.
+    fn publish(contract: &mut Contract, bounty: u64, deposit: u128) -> TaskId {
+        set_context(accounts(1), deposit, 2_000);
+
+        contract.publish_task(task_config(bounty))
+    }
+
+    /// Has `accounts(2)` claim and submit the task, and its owner verify the result.
+    fn complete(contract: &mut Contract, task_id: &TaskId) {
+        set_context(accounts(2), 0, 3_000);
+        contract.claim_task(task_id.clone());
+        contract.submit_task_result(
+            task_id.clone(),
+            "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+            "digest".to_string(),
+        );
+
+        set_context(accounts(1), 0, 3_000);
+        contract.verify_task(task_id.clone());
+    }
+
+    fn escrow(contract: &Contract, task_id: &TaskId) -> u128 {
+        contract.task_manager.get_escrow(task_id).as_yoctonear()
+    }
+
+    #[test]
+    fn test_publish_escrows_deposit() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 150);
+
+        assert_eq!(contract.get_task_status(task_id.clone()), Some(TaskStatus::Published));
+        assert_eq!(escrow(&contract, &task_id), 150);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:500")]
+    fn test_publish_insufficient_deposit() {
+        let mut contract = setup();
+
+        publish(&mut contract, 100, 99);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:310")]
+    fn test_submit_output_not_matching_source() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 100);
+
+        set_context(accounts(2), 0, 3_000);
+        contract.claim_task(task_id.clone());
+        contract.submit_task_result(
+            task_id,
+            "https://example.com/output".to_string(),
+            "digest".to_string(),
+        );
+    }
+
+    #[test]
+    fn test_settle_pays_bounty() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 150);
+
+        complete(&mut contract, &task_id);
+
+        set_context(accounts(3), 0, 3_000 + DEFAULT_CHALLENGE_WINDOW_IN_MS + 1);
+        contract.settle_task(task_id.clone());
+
+        let state = contract.task_manager.get_task_state(&task_id).unwrap();
+        assert_eq!(state.status, TaskStatus::Settled);
+        assert_eq!(state.bounty_paid, U256::from(100u64));
+        assert_eq!(escrow(&contract, &task_id), 0);
+    }
+
+    #[test]
+    fn test_expire_refunds_escrow() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 150);
+
+        set_context(accounts(3), 0, 1_000 + ROUND_DURATION_IN_MS + 1);
+        contract.expire_task(task_id.clone());
+
+        assert_eq!(contract.get_task_status(task_id.clone()), Some(TaskStatus::Expired));
+        assert_eq!(escrow(&contract, &task_id), 0);
+    }
+
+    #[test]
+    fn test_cancel_refunds_escrow() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 150);
+
+        contract.cancel_task(task_id.clone());
+
+        assert_eq!(contract.get_task_status(task_id.clone()), Some(TaskStatus::Cancelled));
+        assert_eq!(escrow(&contract, &task_id), 0);
+    }
+
+    #[test]
+    fn test_finalize_round() {
+        let mut contract = setup();
+        let unclaimed = publish(&mut contract, 100, 100);
+        let verified = publish(&mut contract, 100, 150);
+        let submitted = publish(&mut contract, 100, 100);
+        let cancelled = publish(&mut contract, 100, 100);
+
+        complete(&mut contract, &verified);
+
+        set_context(accounts(2), 0, 3_000);
+        contract.claim_task(submitted.clone());
+        contract.submit_task_result(
+            submitted.clone(),
+            "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+            "digest".to_string(),
+        );
+
+        set_context(accounts(1), 0, 3_000);
+        contract.cancel_task(cancelled.clone());
+
+        set_context(accounts(0), 0, 3_000 + DEFAULT_CHALLENGE_WINDOW_IN_MS + 1);
+        assert_eq!(contract.get_round_status(), RoundStatus::Finalizing);
+
+        assert_eq!(contract.finalize_round(1, 3), None);
+        assert_eq!(contract.get_round_status(), RoundStatus::Finalizing);
+
+        let summary = contract.finalize_round(1, 3).unwrap();
+
+        assert_eq!(summary.tasks_published, 4);
+        assert_eq!(summary.tasks_completed, 1);
+        assert_eq!(summary.tasks_expired, 1);
+        assert_eq!(summary.bounty_paid, U256::from(100u64));
+        assert_eq!(contract.get_round_summary(1), Some(summary));
+        assert_eq!(contract.get_round_status(), RoundStatus::Finalized);
+
+        assert_eq!(contract.get_task_status(unclaimed), Some(TaskStatus::Expired));
+        assert_eq!(contract.get_task_status(verified), Some(TaskStatus::Settled));
+        assert_eq!(contract.get_task_status(submitted), Some(TaskStatus::Submitted));
+        assert_eq!(contract.get_task_status(cancelled), Some(TaskStatus::Cancelled));
+    }
+
+    /// Publishes a task with `accounts(3)` as a verifier and has `accounts(2)` submit it.
+    fn submit_for_review(contract: &mut Contract) -> TaskId {
+        contract.grant_role(accounts(3), Role::Verifier);
+        let task_id = publish(contract, 100, 100);
+
+        set_context(accounts(2), 0, 3_000);
+        contract.claim_task(task_id.clone());
+        contract.submit_task_result(
+            task_id.clone(),
+            "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+            "digest".to_string(),
+        );
+
+        task_id
+    }
+
+    #[test]
+    fn test_verifier_rejects_task() {
+        let mut contract = setup();
+        let task_id = submit_for_review(&mut contract);
+
+        set_context(accounts(3), 0, 4_000);
+        contract.reject_task(task_id.clone());
+
+        assert_eq!(contract.get_task_status(task_id), Some(TaskStatus::Rejected));
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:605")]
+    fn test_owner_cannot_reject_task() {
+        let mut contract = setup();
+        let task_id = submit_for_review(&mut contract);
+
+        set_context(accounts(1), 0, 4_000);
+        contract.reject_task(task_id);
+    }
+
+    #[test]
+    fn test_accept_unverified_task() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 100);
+
+        set_context(accounts(2), 0, 3_000);
+        contract.claim_task(task_id.clone());
+        contract.submit_task_result(
+            task_id.clone(),
+            "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+            "digest".to_string(),
+        );
+
+        set_context(accounts(5), 0, 3_000 + DEFAULT_VERIFICATION_TIMEOUT_IN_MS + 1);
+        contract.accept_task(task_id.clone());
+
+        assert_eq!(contract.get_task_status(task_id), Some(TaskStatus::Verified));
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:318")]
+    fn test_accept_task_before_verification_timeout() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 100);
+
+        set_context(accounts(2), 0, 3_000);
+        contract.claim_task(task_id.clone());
+        contract.submit_task_result(
+            task_id.clone(),
+            "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+            "digest".to_string(),
+        );
+
+        set_context(accounts(5), 0, 3_000 + DEFAULT_VERIFICATION_TIMEOUT_IN_MS);
+        contract.accept_task(task_id);
+    }
+
+    #[test]
+    fn test_finalize_round_accepts_unverified_task() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 100);
+
+        set_context(accounts(2), 0, 3_000);
+        contract.claim_task(task_id.clone());
+        contract.submit_task_result(
+            task_id.clone(),
+            "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+            "digest".to_string(),
+        );
+
+        set_context(accounts(0), 0, 3_000 + DEFAULT_VERIFICATION_TIMEOUT_IN_MS + 1);
+        let summary = contract.finalize_round(1, 10).unwrap();
+
+        assert_eq!(summary.tasks_completed, 1);
+        assert_eq!(summary.bounty_paid, U256::from(100u64));
+        assert_eq!(contract.get_task_status(task_id), Some(TaskStatus::Settled));
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:202")]
+    fn test_finalize_running_round() {
+        let mut contract = setup();
+
+        contract.finalize_round(1, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:202")]
+    fn test_finalize_round_twice() {
+        let mut contract = setup();
+        publish(&mut contract, 100, 100);
+
+        set_context(accounts(0), 0, 1_000 + ROUND_DURATION_IN_MS + 1);
+        contract.finalize_round(1, 10);
+        contract.finalize_round(1, 10);
+    }
+
+    #[test]
+    fn test_update_task_offer_top_up() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 100);
+
+        set_context(accounts(1), 100, 2_000);
+        contract.update_task_offer(
+            task_id.clone(),
+            vec![TaskOffer {
+                bounty: U256::from(200u64),
+                requirements: None,
+            }],
+        );
+
+        let task = contract.task_manager.get_task(&task_id).unwrap();
+        assert_eq!(task.offer[0].bounty, U256::from(200u64));
+        assert_eq!(escrow(&contract, &task_id), 200);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:500")]
+    fn test_update_task_offer_without_top_up() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 100);
+
+        set_context(accounts(1), 0, 2_000);
+        contract.update_task_offer(
+            task_id,
+            vec![TaskOffer {
+                bounty: U256::from(200u64),
+                requirements: None,
+            }],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:312")]
+    fn test_update_task_offer_validates_offers() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 100);
+
+        contract.update_task_offer(task_id, vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:308")]
+    fn test_update_task_offer_bounty_decreased() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 100);
+
+        contract.update_task_offer(
+            task_id,
+            vec![TaskOffer {
+                bounty: U256::from(50u64),
+                requirements: None,
+            }],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:301")]
+    fn test_cancel_claimed_task() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 100);
+
+        set_context(accounts(2), 0, 3_000);
+        contract.claim_task(task_id.clone());
+
+        set_context(accounts(1), 0, 3_000);
+        contract.cancel_task(task_id);
+    }
+
+    #[test]
+    fn test_publish_gated_by_task_types() {
+        let mut contract = setup();
+
+        set_context(accounts(0), 0, 2_000);
+        contract.register_task_type(TaskType {
+            name: "IMAGE_RESIZING".to_string(),
+            version: 1,
+            description: "Image resizing tasks".to_string(),
+            schema_cid: "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+        });
+
+        set_context(accounts(1), 100, 2_000);
+        contract.publish_task(TaskConfig {
+            r#type: "IMAGE_RESIZING".to_string(),
+            ..task_config(100)
+        });
+
+        set_context(accounts(0), 0, 2_000);
+        contract.unregister_task_type("MEDIA_TRANSCODING".to_string());
+
+        assert!(contract.get_task_type("MEDIA_TRANSCODING".to_string()).is_none());
+        assert_eq!(contract.count_tasks(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:314")]
+    fn test_publish_unregistered_task_type() {
+        let mut contract = setup();
+
+        set_context(accounts(0), 0, 2_000);
+        contract.unregister_task_type("MEDIA_TRANSCODING".to_string());
+
+        publish(&mut contract, 100, 100);
+    }
+
+    #[test]
+    fn test_stake_unbonds_for_configured_rounds() {
+        let mut contract = setup();
+
+        set_context(accounts(2), 100, 2_000);
+        assert_eq!(contract.stake(), U256::from(100u64));
+        assert_eq!(contract.unstake(U256::from(40u64)), U256::from(60u64));
+
+        let unbonding = contract.get_unbonding_stake(accounts(2)).unwrap();
+        assert_eq!(unbonding.unlock_round, 1 + DEFAULT_UNBONDING_ROUNDS);
+
+        for round in 1..=DEFAULT_UNBONDING_ROUNDS {
+            set_context(accounts(0), 0, 1_000 + round as u64 * (ROUND_DURATION_IN_MS + 1));
+            contract.start_new_round();
+        }
+
+        set_context(
+            accounts(2),
+            0,
+            1_000 + DEFAULT_UNBONDING_ROUNDS as u64 * (ROUND_DURATION_IN_MS + 1),
+        );
+        assert_eq!(contract.withdraw_unstaked(), U256::from(60u64));
+        assert!(contract.get_unbonding_stake(accounts(2)).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:501")]
+    fn test_withdraw_before_unbonded() {
+        let mut contract = setup();
+
+        set_context(accounts(2), 100, 2_000);
+        contract.stake();
+        contract.unstake(U256::from(40u64));
+        contract.withdraw_unstaked();
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:401")]
+    fn test_unstake_above_u128() {
+        let mut contract = setup();
+
+        set_context(accounts(2), 100, 2_000);
+        contract.stake();
+        contract.unstake(U256::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:503")]
+    fn test_dissent_slash_above_u128() {
+        let mut contract = setup();
+
+        contract.set_dissent_slash(U256::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:503")]
+    fn test_dispute_slash_above_u128() {
+        let mut contract = setup();
+
+        contract.set_dispute_slash(U256::MAX);
+    }
+
+    /// Publishes and completes a task with `accounts(3)` drawn as its only verifier, then
+    /// has `accounts(4)` dispute its result at 4s.
+    fn dispute(contract: &mut Contract) -> TaskId {
+        set_context(accounts(0), 0, 1_500);
+        contract.set_assignment_sizes(0, 1);
+        contract.set_dispute_slash(U256::from(5u64));
+        contract.grant_role(accounts(3), Role::Verifier);
+
+        for staker in 2..=4 {
+            set_context(accounts(staker), 100, 1_500);
+            contract.stake();
+        }
+
+        let task_id = publish(contract, 100, 100);
+        complete(contract, &task_id);
+
+        set_context(accounts(4), 10, 4_000);
+        contract.dispute_result(task_id.clone(), "other".to_string());
+
+        task_id
+    }
+
+    #[test]
+    fn test_dispute_upheld() {
+        let mut contract = setup();
+        let task_id = dispute(&mut contract);
+
+        set_context(accounts(3), 0, 5_000);
+        contract.vote_on_dispute(task_id.clone(), "other".to_string());
+
+        assert_eq!(contract.get_task_status(task_id.clone()), Some(TaskStatus::Rejected));
+        assert_eq!(contract.get_dispute(task_id).unwrap().status, DisputeStatus::Upheld);
+        // The worker loses the configured slash, not the challenger's bond.
+        assert_eq!(contract.get_user_stake(accounts(2)), U256::from(95u64));
+    }
+
+    #[test]
+    fn test_dispute_expires() {
+        let mut contract = setup();
+        let task_id = dispute(&mut contract);
+
+        set_context(accounts(5), 0, 4_000 + DEFAULT_DISPUTE_TIMEOUT_IN_MS + 1);
+        contract.expire_dispute(task_id.clone());
+
+        assert_eq!(contract.get_task_status(task_id.clone()), Some(TaskStatus::Verified));
+        assert_eq!(contract.get_dispute(task_id).unwrap().status, DisputeStatus::Expired);
+        assert_eq!(contract.get_user_stake(accounts(2)), U256::from(100u64));
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:607")]
+    fn test_expire_dispute_before_deadline() {
+        let mut contract = setup();
+        let task_id = dispute(&mut contract);
+
+        contract.expire_dispute(task_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:100")]
+    fn test_owner_cannot_vote_on_dispute() {
+        let mut contract = setup();
+        let task_id = dispute(&mut contract);
+
+        set_context(accounts(0), 0, 5_000);
+        contract.vote_on_dispute(task_id, "other".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:606")]
+    fn test_dispute_without_committee() {
+        let mut contract = setup();
+        let task_id = publish(&mut contract, 100, 100);
+
+        complete(&mut contract, &task_id);
+
+        set_context(accounts(4), 100, 4_000);
+        contract.stake();
+        contract.dispute_result(task_id, "other".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:100")]
+    fn test_cross_contract_call_has_no_owner_rights() {
+        let mut contract = setup();
+
+        set_call_context(accounts(0), accounts(5), 0, 2_000);
+        contract.grant_role(accounts(5), Role::Verifier);
+    }
+
+    #[test]
+    fn test_stake_credits_predecessor() {
+        let mut contract = setup();
+
+        set_call_context(accounts(0), accounts(5), 100, 2_000);
+        contract.stake();
+
+        assert_eq!(contract.get_user_stake(accounts(5)), U256::from(100u64));
+        assert_eq!(contract.get_user_stake(accounts(0)), U256::zero());
+    }
+
+    #[test]
+    fn test_staked_owner_not_drawn_as_verifier() {
+        let mut contract = setup();
+
+        set_context(accounts(0), 1_000, 1_500);
+        contract.set_assignment_sizes(0, 2);
+        contract.grant_role(accounts(3), Role::Verifier);
+        contract.stake();
+
+        set_context(accounts(3), 100, 1_500);
+        contract.stake();
+
+        let task_id = publish(&mut contract, 100, 100);
+
+        assert_eq!(
+            contract.get_assignments(task_id).unwrap().verifiers,
+            vec![accounts(3)]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:500")]
+    fn test_set_user_props_without_storage_deposit() {
+        let mut contract = setup();
+
+        set_context(accounts(2), 0, 2_000);
+        contract.set_user_props(vec![("cpu_cores".to_string(), U256::from(8u64))]);
+    }
+
+    #[test]
+    fn test_set_user_endpoints_pays_storage() {
+        let mut contract = setup();
+
+        set_context(accounts(2), 10u128.pow(22), 2_000);
+        contract.set_user_endpoints(BTreeMap::from([(
+            EndpointProtocol::Http,
+            "http://127.0.0.1:8080".to_string(),
+        )]));
+
+        assert_eq!(
+            contract.get_user_endpoint(accounts(2)),
+            Some("http://127.0.0.1:8080".to_string())
+        );
+    }
+}
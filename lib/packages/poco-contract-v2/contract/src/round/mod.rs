@@ -1,3 +1,3 @@
-pub use round_manager::RoundManager;
+pub use round_manager::{FinalizationProgress, RoundManager};
 
 mod round_manager;
@@ -1,16 +1,44 @@
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::store::LookupMap;
+use poco_types::types::error::PocoError;
 use poco_types::types::event::EventNonce;
-use poco_types::types::round::{BlockTimestamp, RoundDuration, RoundId, RoundStatus};
+use poco_types::types::round::{BlockTimestamp, RoundDuration, RoundId, RoundStatus, RoundSummary};
+use poco_types::types::uint::U256;
 
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Debug, PartialEq)]
+use crate::util::ensure;
+
+/// Timing and event window of a round. `event_end` is `None` while the round is the
+/// latest one, since events keep being appended to its window until the next round starts.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, PartialEq)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct RoundRecord {
+    pub start_time: BlockTimestamp,
+    pub duration: RoundDuration,
+    pub event_offset: EventNonce,
+    pub event_end: Option<EventNonce>,
+}
+
+/// Totals of a round whose finalization spans several calls, and the nonce of the next
+/// task to finalize.
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, Default, PartialEq)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct FinalizationProgress {
+    pub next_task_nonce: u32,
+    pub tasks_completed: u32,
+    pub tasks_expired: u32,
+    pub bounty_paid: U256,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
 #[borsh(crate = "near_sdk::borsh")]
-#[serde(crate = "near_sdk::serde")]
 pub struct RoundManager {
     round_id: RoundId,
     round_start_time: BlockTimestamp,
     round_duration: RoundDuration,
     round_event_offset: EventNonce,
+    summaries: LookupMap<RoundId, RoundSummary>,
+    history: LookupMap<RoundId, RoundRecord>,
+    progress: LookupMap<RoundId, FinalizationProgress>,
 }
 
 impl RoundManager {
@@ -20,19 +48,34 @@ impl RoundManager {
             round_start_time: 0.into(),
             round_duration: initial_round_duration,
             round_event_offset: 0,
+            summaries: LookupMap::new(b"round-manager:summaries".to_vec()),
+            history: LookupMap::new(b"round-manager:history".to_vec()),
+            progress: LookupMap::new(b"round-manager:progress".to_vec()),
         }
     }
 
     #[inline]
-    pub fn start_new_round(&mut self, event_offset: u32) -> RoundId {
-        assert_eq!(
-            self.get_round_status(),
-            RoundStatus::Pending,
-            "current round has not ended yet."
+    pub fn start_new_round(&mut self, event_offset: u32, round_duration: RoundDuration) -> RoundId {
+        ensure!(
+            self.get_round_status() != RoundStatus::Running,
+            PocoError::RoundStillRunning
         );
 
+        if self.round_id > 0 {
+            self.history.insert(
+                self.round_id,
+                RoundRecord {
+                    start_time: self.round_start_time,
+                    duration: self.round_duration,
+                    event_offset: self.round_event_offset,
+                    event_end: Some(event_offset),
+                },
+            );
+        }
+
         self.round_id += 1;
         self.round_start_time = near_sdk::env::block_timestamp_ms().into();
+        self.round_duration = round_duration;
 
         self.round_event_offset = event_offset;
 
@@ -40,18 +83,92 @@ impl RoundManager {
     }
 
     #[inline]
-    pub fn get_round_status(&self) -> RoundStatus {
+    pub fn is_round_running(&self) -> bool {
         let block_time: BlockTimestamp = near_sdk::env::block_timestamp_ms().into();
         let start_time = self.round_start_time;
         let end_time = self.round_start_time + self.round_duration;
 
-        if block_time.between(start_time, end_time) {
+        block_time.between(start_time, end_time)
+    }
+
+    #[inline]
+    pub fn get_round_status(&self) -> RoundStatus {
+        if self.is_round_running() {
             RoundStatus::Running
         } else {
+            self.get_ended_round_status(self.round_id)
+        }
+    }
+
+    /// Status of a round that is no longer running. Ended rounds report `Finalizing` rather
+    /// than `Pending` until they are finalized, see `RoundStatus`.
+    #[inline]
+    fn get_ended_round_status(&self, round_id: RoundId) -> RoundStatus {
+        if self.summaries.contains_key(&round_id) {
+            RoundStatus::Finalized
+        } else if round_id == 0 {
             RoundStatus::Pending
+        } else {
+            RoundStatus::Finalizing
         }
     }
 
+    /// Status of any round up to the current one, `None` for rounds that have not started.
+    #[inline]
+    pub fn get_round_status_by_id(&self, round_id: RoundId) -> Option<RoundStatus> {
+        if round_id > self.round_id {
+            None
+        } else if round_id == self.round_id {
+            Some(self.get_round_status())
+        } else {
+            Some(self.get_ended_round_status(round_id))
+        }
+    }
+
+    /// Record of any round that has been started, including the current one.
+    #[inline]
+    pub fn get_round_record(&self, round_id: RoundId) -> Option<RoundRecord> {
+        if round_id == 0 || round_id > self.round_id {
+            None
+        } else if round_id == self.round_id {
+            Some(RoundRecord {
+                start_time: self.round_start_time,
+                duration: self.round_duration,
+                event_offset: self.round_event_offset,
+                event_end: None,
+            })
+        } else {
+            self.history.get(&round_id).cloned()
+        }
+    }
+
+    #[inline]
+    pub fn get_round_summary(&self, round_id: RoundId) -> Option<&RoundSummary> {
+        self.summaries.get(&round_id)
+    }
+
+    /// Progress of a round being finalized, from its first task if none was finalized yet.
+    #[inline]
+    pub fn get_finalization_progress(&self, round_id: RoundId) -> FinalizationProgress {
+        self.progress.get(&round_id).cloned().unwrap_or_default()
+    }
+
+    #[inline]
+    pub fn set_finalization_progress(&mut self, round_id: RoundId, progress: FinalizationProgress) {
+        self.progress.insert(round_id, progress);
+    }
+
+    #[inline]
+    pub fn finalize_round(&mut self, summary: RoundSummary) {
+        ensure!(
+            self.get_round_status_by_id(summary.round_id) == Some(RoundStatus::Finalizing),
+            PocoError::RoundNotFinalizing
+        );
+
+        self.progress.remove(&summary.round_id);
+        self.summaries.insert(summary.round_id, summary);
+    }
+
     #[inline]
     pub fn get_round_id(&self) -> RoundId {
         self.round_id
@@ -72,3 +189,93 @@ impl RoundManager {
         self.round_event_offset
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+    use poco_types::types::uint::U256;
+
+    use super::*;
+
+    fn set_block_time(timestamp_in_ms: u64) {
+        testing_env!(VMContextBuilder::new()
+            .block_timestamp(timestamp_in_ms * 1_000_000)
+            .build());
+    }
+
+    #[test]
+    fn test_round_history() {
+        set_block_time(1_000);
+        let mut round_manager = RoundManager::new(0, RoundDuration::new(100));
+
+        assert_eq!(round_manager.get_round_record(0), None);
+        assert_eq!(round_manager.start_new_round(0, RoundDuration::new(100)), 1);
+
+        set_block_time(2_000);
+        assert_eq!(round_manager.start_new_round(5, RoundDuration::new(200)), 2);
+
+        assert_eq!(
+            round_manager.get_round_record(1),
+            Some(RoundRecord {
+                start_time: 1_000.into(),
+                duration: RoundDuration::new(100),
+                event_offset: 0,
+                event_end: Some(5),
+            })
+        );
+        assert_eq!(
+            round_manager.get_round_record(2),
+            Some(RoundRecord {
+                start_time: 2_000.into(),
+                duration: RoundDuration::new(200),
+                event_offset: 5,
+                event_end: None,
+            })
+        );
+        assert_eq!(round_manager.get_round_record(3), None);
+    }
+
+    #[test]
+    fn test_round_finalization() {
+        set_block_time(1_000);
+        let mut round_manager = RoundManager::new(0, RoundDuration::new(100));
+
+        assert_eq!(round_manager.get_round_status(), RoundStatus::Pending);
+        assert_eq!(round_manager.start_new_round(0, RoundDuration::new(100)), 1);
+        assert_eq!(round_manager.get_round_status(), RoundStatus::Running);
+
+        set_block_time(1_200);
+        assert_eq!(round_manager.get_round_status(), RoundStatus::Finalizing);
+
+        round_manager.finalize_round(RoundSummary {
+            round_id: 1,
+            tasks_published: 0,
+            tasks_completed: 0,
+            tasks_expired: 0,
+            bounty_paid: U256::zero(),
+            finalized_at: 1_200.into(),
+        });
+
+        assert_eq!(round_manager.get_round_status_by_id(1), Some(RoundStatus::Finalized));
+        assert_eq!(round_manager.get_round_summary(1).unwrap().finalized_at, 1_200.into());
+        assert_eq!(round_manager.get_round_status_by_id(2), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:202")]
+    fn test_finalize_running_round() {
+        set_block_time(1_000);
+        let mut round_manager = RoundManager::new(0, RoundDuration::new(100));
+        round_manager.start_new_round(0, RoundDuration::new(100));
+
+        round_manager.finalize_round(RoundSummary {
+            round_id: 1,
+            tasks_published: 0,
+            tasks_completed: 0,
+            tasks_expired: 0,
+            bounty_paid: U256::zero(),
+            finalized_at: 1_000.into(),
+        });
+    }
+}
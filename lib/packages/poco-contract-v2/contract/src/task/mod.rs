@@ -1,4 +1,5 @@
-pub use task_manager::TaskManager;
+pub use task_manager::{QuorumOutcome, TaskManager};
+pub use task_type_registry::TaskTypeRegistry;
 
 mod task_manager;
-
+mod task_type_registry;
@@ -1,24 +1,55 @@
-use near_sdk::{AccountId, env};
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{AccountId, NearToken, env};
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::store::{LookupMap, Vector};
-use poco_types::types::round::RoundId;
-use poco_types::types::task::{OnChainTaskConfig, TaskConfig};
+use poco_types::types::error::PocoError;
+use poco_types::types::round::{BlockTimestamp, RoundDuration, RoundId};
+use poco_types::types::task::{OnChainTaskConfig, TaskConfig, TaskOffer};
 use poco_types::types::task::id::TaskId;
+use poco_types::types::task::status::{TaskAttempt, TaskResult, TaskState, TaskStatus};
+use poco_types::types::uint::U256;
+
+use crate::util::ensure;
+
+/// Agreement among the results of a replicated task.
+pub enum QuorumOutcome {
+    /// Neither enough agreeing results nor all replicas are in yet.
+    Pending,
+    /// `quorum` results agree on `digest`, `dissenters` reported another digest.
+    Reached {
+        digest: String,
+        dissenters: Vec<(AccountId, String)>,
+    },
+    /// Every replica reported and no digest reached the quorum.
+    Failed,
+}
 
 #[derive(BorshDeserialize, BorshSerialize)]
 #[borsh(crate = "near_sdk::borsh")]
 pub struct TaskManager {
     tasks: LookupMap<RoundId, Vector<OnChainTaskConfig>>,
+    states: LookupMap<TaskId, TaskState>,
+    escrow: LookupMap<TaskId, NearToken>,
+    owner_tasks: LookupMap<AccountId, Vector<TaskId>>,
+    worker_tasks: LookupMap<AccountId, Vector<TaskId>>,
     count: u64,
 }
 
 impl TaskManager {
     pub fn new() -> Self {
         let tasks = LookupMap::new(b"task-manager:tasks".to_vec());
+        let states = LookupMap::new(b"task-manager:states".to_vec());
+        let escrow = LookupMap::new(b"task-manager:escrow".to_vec());
+        let owner_tasks = LookupMap::new(b"task-manager:owner-tasks".to_vec());
+        let worker_tasks = LookupMap::new(b"task-manager:worker-tasks".to_vec());
 
-        // 这里要多做一个数据结构，用于保存每一个task的完成状态（可能有多次尝试）
-
-        TaskManager { tasks, count: 0 }
+        TaskManager {
+            tasks,
+            states,
+            escrow,
+            owner_tasks,
+            worker_tasks,
+            count: 0,
+        }
     }
 
 
@@ -27,18 +58,64 @@ impl TaskManager {
         &self,
         round_id: u32,
         task_nounce: u32
-    ) -> OnChainTaskConfig {
+    ) -> Option<OnChainTaskConfig> {
         self.tasks
             .get(&round_id)
-            .and_then(|tasks| tasks.get(task_nounce).cloned()).unwrap()
+            .and_then(|tasks| tasks.get(task_nounce).cloned())
+    }
+
+    #[inline]
+    pub fn list_tasks(&self, round_id: RoundId, from: u32, limit: u32) -> Vec<OnChainTaskConfig> {
+        self.tasks
+            .get(&round_id)
+            .map(|tasks| {
+                tasks
+                    .iter()
+                    .skip(from as usize)
+                    .take(limit as usize)
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Tasks published by `owner`, oldest first.
+    #[inline]
+    pub fn list_tasks_by_owner(&self, owner: &AccountId, from: u32, limit: u32) -> Vec<OnChainTaskConfig> {
+        self.list_indexed_tasks(self.owner_tasks.get(owner), from, limit)
     }
 
+    /// Tasks `worker` has claimed at least once, in the order of their first claim.
     #[inline]
-    pub fn show_tasks(
+    pub fn list_tasks_by_worker(&self, worker: &AccountId, from: u32, limit: u32) -> Vec<OnChainTaskConfig> {
+        self.list_indexed_tasks(self.worker_tasks.get(worker), from, limit)
+    }
+
+    fn list_indexed_tasks(
         &self,
-        round_id: u32,
-    ) -> Option<&Vector<OnChainTaskConfig>> {
-        self.tasks.get(&round_id)
+        index: Option<&Vector<TaskId>>,
+        from: u32,
+        limit: u32,
+    ) -> Vec<OnChainTaskConfig> {
+        index
+            .map(|task_ids| {
+                task_ids
+                    .iter()
+                    .skip(from as usize)
+                    .take(limit as usize)
+                    .filter_map(|task_id| self.get_task(task_id).cloned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn index_task(index: &mut LookupMap<AccountId, Vector<TaskId>>, prefix: &str, account: AccountId, task_id: TaskId) {
+        let prefix = format!("{prefix}:{account}");
+
+        index
+            .entry(account)
+            .or_insert_with(|| Vector::new(prefix.as_bytes().to_vec()))
+            .push(task_id);
     }
 
     // #[inline]
@@ -63,17 +140,373 @@ impl TaskManager {
         });
 
         let task_id = TaskId::new(round_id, tasks_for_round.len());
-        let config = config.to_on_chain_task_config(owner, task_id.clone());
+        let config = config.to_on_chain_task_config(owner.clone(), task_id.clone());
+
+        tasks_for_round.push(config.clone());
+
+        let now: BlockTimestamp = env::block_timestamp_ms().into();
+        self.states.insert(task_id.clone(), TaskState::new(now));
+        Self::index_task(&mut self.owner_tasks, "task-manager:owner-tasks", owner, task_id.clone());
+
+        self.count += 1;
+
+        (task_id, config)
+    }
+
+    #[inline]
+    pub fn get_task(&self, task_id: &TaskId) -> Option<&OnChainTaskConfig> {
+        self.tasks
+            .get(&task_id.get_round_id())
+            .and_then(|tasks| tasks.get(task_id.get_task_nonce()))
+    }
+
+    /// Replaces the offers of the task, returning the updated config.
+    pub fn update_task_offer(&mut self, task_id: &TaskId, offer: Vec<TaskOffer>) -> &OnChainTaskConfig {
+        let task = self
+            .tasks
+            .get_mut(&task_id.get_round_id())
+            .and_then(|tasks| tasks.get_mut(task_id.get_task_nonce()))
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic());
+
+        task.offer = offer;
+
+        task
+    }
+
+    #[inline]
+    pub fn get_task_state(&self, task_id: &TaskId) -> Option<&TaskState> {
+        self.states.get(task_id)
+    }
+
+    #[inline]
+    pub fn get_task_status(&self, task_id: &TaskId) -> Option<TaskStatus> {
+        self.states.get(task_id).map(|state| state.status)
+    }
+
+    /// Moves the task to `next`, panicking if the lifecycle does not allow it.
+    /// Returns the status the task was in before the transition.
+    pub fn transit_task(&mut self, task_id: &TaskId, next: TaskStatus) -> TaskStatus {
+        let state = self
+            .states
+            .get_mut(task_id)
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic());
+        let prev = state.status;
+
+        if !prev.can_transit_to(next) {
+            PocoError::InvalidTaskTransition.panic_with(format_args!("from {prev} to {next}"));
+        }
+
+        state.status = next;
+        state.updated_at = env::block_timestamp_ms().into();
+
+        // A task published again starts over from the results it already has, the
+        // timed out claims are dropped so their workers are not held to them anymore.
+        if next == TaskStatus::Published {
+            state.attempts.retain(|attempt| attempt.result.is_some());
+            state.flagged.clear();
+            state.agreed_digest = None;
+        }
+
+        prev
+    }
+
+    /// Records a claim by `worker` and moves the task to `Claimed`. A replicated task can be
+    /// claimed again by other workers until every replica is taken by a result or a claim
+    /// that has not timed out.
+    pub fn claim_task(&mut self, task_id: &TaskId, worker: AccountId, offer_index: Option<u32>) {
+        let (replicas, execution_timeout) = self.get_replication(task_id);
+        let status = self.get_task_status(task_id);
+
+        if replicas > 1 {
+            let now: BlockTimestamp = env::block_timestamp_ms().into();
+            let taken: Vec<&TaskAttempt> = self
+                .states
+                .get(task_id)
+                .map(|state| {
+                    state
+                        .attempts
+                        .iter()
+                        .filter(|attempt| {
+                            attempt.result.is_some()
+                                || !Self::is_attempt_expired(attempt, execution_timeout, now)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            ensure!(
+                taken.len() < replicas as usize
+                    && taken.iter().all(|attempt| attempt.worker != worker),
+                PocoError::TaskAlreadyClaimed
+            );
+        }
+
+        if replicas <= 1 || status != Some(TaskStatus::Claimed) {
+            self.transit_task(task_id, TaskStatus::Claimed);
+        }
+
+        let claimed_before = self
+            .states
+            .get(task_id)
+            .is_some_and(|state| state.attempts.iter().any(|attempt| attempt.worker == worker));
+
+        if !claimed_before {
+            Self::index_task(
+                &mut self.worker_tasks,
+                "task-manager:worker-tasks",
+                worker.clone(),
+                task_id.clone(),
+            );
+        }
+
+        self.push_attempt(
+            task_id,
+            TaskAttempt {
+                worker,
+                claimed_at: env::block_timestamp_ms().into(),
+                offer_index,
+                result: None,
+            },
+        );
+    }
+
+    /// Attaches the result to the worker's open attempt. A task with a single replica moves
+    /// to `Submitted`, a replicated one stays claimed until its results are compared.
+    pub fn submit_task_result(
+        &mut self,
+        task_id: &TaskId,
+        worker: AccountId,
+        output: String,
+        digest: String,
+    ) -> TaskResult {
+        let (replicas, execution_timeout) = self.get_replication(task_id);
+        let now: BlockTimestamp = env::block_timestamp_ms().into();
+
+        let state = self
+            .states
+            .get_mut(task_id)
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic());
 
-        if let Ok(config) = config {
-            tasks_for_round.push(config.clone());
+        ensure!(state.status == TaskStatus::Claimed, PocoError::NoOpenAttempt);
 
-            self.count += 1;
+        let attempt = state
+            .attempts
+            .iter_mut()
+            .rev()
+            .find(|attempt| attempt.worker == worker && attempt.result.is_none())
+            .unwrap_or_else(|| PocoError::NoOpenAttempt.panic());
 
-            (task_id, config)
-        } else {
-            env::panic_str("Failed to publish task");
+        ensure!(
+            !Self::is_attempt_expired(attempt, execution_timeout, now),
+            PocoError::ExecutionTimedOut
+        );
+
+        let result = TaskResult {
+            worker,
+            submitted_at: env::block_timestamp_ms().into(),
+            output,
+            digest,
+        };
+        attempt.result = Some(result.clone());
+
+        if replicas <= 1 {
+            self.transit_task(task_id, TaskStatus::Submitted);
         }
+
+        result
+    }
+
+    /// Compares the output digests of a replicated task's results against its quorum.
+    pub fn check_quorum(&self, task_id: &TaskId) -> QuorumOutcome {
+        let task = self
+            .get_task(task_id)
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic());
+        let results: Vec<&TaskResult> = self
+            .states
+            .get(task_id)
+            .map(|state| state.results().collect())
+            .unwrap_or_default();
+
+        let agreed = results.iter().map(|result| &result.digest).find(|digest| {
+            results
+                .iter()
+                .filter(|result| result.digest == **digest)
+                .count()
+                >= task.quorum as usize
+        });
+
+        match agreed {
+            Some(digest) => QuorumOutcome::Reached {
+                digest: digest.clone(),
+                dissenters: results
+                    .iter()
+                    .filter(|result| result.digest != *digest)
+                    .map(|result| (result.worker.clone(), result.digest.clone()))
+                    .collect(),
+            },
+            None if results.len() >= task.replicas as usize => QuorumOutcome::Failed,
+            None => QuorumOutcome::Pending,
+        }
+    }
+
+    /// Records the digest a quorum agreed on and the workers that disagreed with it.
+    pub fn record_consensus(&mut self, task_id: &TaskId, digest: String, flagged: Vec<AccountId>) {
+        let state = self
+            .states
+            .get_mut(task_id)
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic());
+
+        state.agreed_digest = Some(digest);
+        state.flagged = flagged;
+    }
+
+    /// Attempts whose workers earn their bounty when the task is settled: those that reported
+    /// the agreed digest of a replicated task, otherwise the latest attempt.
+    pub fn get_rewarded_attempts(&self, task_id: &TaskId) -> Vec<&TaskAttempt> {
+        let Some(state) = self.states.get(task_id) else {
+            return Vec::new();
+        };
+
+        match &state.agreed_digest {
+            Some(digest) => state
+                .attempts
+                .iter()
+                .filter(|attempt| {
+                    attempt
+                        .result
+                        .as_ref()
+                        .is_some_and(|result| result.digest == *digest)
+                })
+                .collect(),
+            None => state.current_attempt().into_iter().collect(),
+        }
+    }
+
+    /// Workers of the attempts that are still waiting for a result.
+    pub fn get_open_workers(&self, task_id: &TaskId) -> Vec<AccountId> {
+        self.states
+            .get(task_id)
+            .map(|state| {
+                state
+                    .attempts
+                    .iter()
+                    .filter(|attempt| attempt.result.is_none())
+                    .map(|attempt| attempt.worker.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    pub fn get_task_results(&self, task_id: &TaskId) -> Vec<TaskResult> {
+        self.states
+            .get(task_id)
+            .map(|state| state.results().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    pub fn set_bounty_paid(&mut self, task_id: &TaskId, amount: NearToken) {
+        self.states
+            .get_mut(task_id)
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic())
+            .bounty_paid = U256::from(amount.as_yoctonear());
+    }
+
+    /// Time at which the claims of the task time out, if the task is claimed and has an
+    /// execution timeout. For a replicated task this is when its latest open claim times out.
+    pub fn get_claim_timeout(&self, task_id: &TaskId) -> Option<BlockTimestamp> {
+        let execution_timeout = self.get_task(task_id)?.execution_timeout?;
+        let state = self.states.get(task_id)?;
+
+        if state.status != TaskStatus::Claimed {
+            return None;
+        }
+
+        state
+            .attempts
+            .iter()
+            .filter(|attempt| attempt.result.is_none())
+            .map(|attempt| attempt.claimed_at + execution_timeout)
+            .max()
+    }
+
+    /// Time until which the accepted result of the task can be disputed, counted from the
+    /// latest submission of the rewarded attempts.
+    pub fn get_challenge_deadline(&self, task_id: &TaskId, window: RoundDuration) -> Option<BlockTimestamp> {
+        self.get_rewarded_attempts(task_id)
+            .into_iter()
+            .filter_map(|attempt| attempt.result.as_ref())
+            .map(|result| result.submitted_at + window)
+            .max()
+    }
+
+    fn get_replication(&self, task_id: &TaskId) -> (u32, Option<RoundDuration>) {
+        self.get_task(task_id)
+            .map(|task| (task.replicas, task.execution_timeout))
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic())
+    }
+
+    #[inline]
+    fn is_attempt_expired(
+        attempt: &TaskAttempt,
+        execution_timeout: Option<RoundDuration>,
+        now: BlockTimestamp,
+    ) -> bool {
+        execution_timeout.is_some_and(|timeout| now > attempt.claimed_at + timeout)
+    }
+
+    #[inline]
+    pub fn is_claim_timed_out(&self, task_id: &TaskId) -> bool {
+        let now: BlockTimestamp = env::block_timestamp_ms().into();
+
+        self.get_claim_timeout(task_id)
+            .is_some_and(|timeout| now > timeout)
+    }
+
+    #[inline]
+    pub fn is_claim_deadline_passed(&self, task_id: &TaskId) -> bool {
+        let now: BlockTimestamp = env::block_timestamp_ms().into();
+
+        self.get_task(task_id)
+            .and_then(|task| task.claim_deadline)
+            .is_some_and(|deadline| now > deadline)
+    }
+
+    #[inline]
+    pub fn push_attempt(&mut self, task_id: &TaskId, attempt: TaskAttempt) {
+        self.states
+            .get_mut(task_id)
+            .unwrap_or_else(|| PocoError::TaskNotFound.panic())
+            .attempts
+            .push(attempt);
+    }
+
+    #[inline]
+    pub fn deposit_escrow(&mut self, task_id: &TaskId, amount: NearToken) {
+        let escrowed = self
+            .escrow
+            .get(task_id)
+            .copied()
+            .unwrap_or(NearToken::from_yoctonear(0));
+
+        self.escrow.insert(task_id.clone(), escrowed.saturating_add(amount));
+    }
+
+    #[inline]
+    pub fn get_escrow(&self, task_id: &TaskId) -> NearToken {
+        self.escrow
+            .get(task_id)
+            .copied()
+            .unwrap_or(NearToken::from_yoctonear(0))
+    }
+
+    /// Removes and returns everything held in escrow for the task.
+    #[inline]
+    pub fn take_escrow(&mut self, task_id: &TaskId) -> NearToken {
+        self.escrow
+            .remove(task_id)
+            .unwrap_or(NearToken::from_yoctonear(0))
     }
 
     #[inline]
@@ -104,4 +537,255 @@ impl Default for TaskManager {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+    use poco_types::types::round::RoundDuration;
+    use poco_types::types::task::{TaskInputSource, TaskOutputSource};
+
+    use super::*;
+
+    fn task_config() -> TaskConfig {
+        TaskConfig {
+            input: TaskInputSource::Link {
+                url: "https://example.com/input.mp4".to_string(),
+            },
+            output: TaskOutputSource::Ipfs,
+            requirements: vec![],
+            offer: vec![],
+            config: vec![],
+            r#type: "MEDIA_TRANSCODING".to_string(),
+            claim_deadline: None,
+            execution_timeout: None,
+            replicas: 1,
+            quorum: 1,
+        }
+    }
+
+    #[test]
+    fn test_task_lifecycle() {
+        let mut task_manager = TaskManager::new();
+        let (task_id, _) = task_manager.publish_task(1, accounts(0), task_config());
+
+        assert_eq!(
+            task_manager.get_task_status(&task_id),
+            Some(TaskStatus::Published)
+        );
+
+        task_manager.claim_task(&task_id, accounts(1), None);
+        task_manager.submit_task_result(
+            &task_id,
+            accounts(1),
+            "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+            "digest".to_string(),
+        );
+
+        let results = task_manager.get_task_results(&task_id);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].worker, accounts(1));
+
+        task_manager.transit_task(&task_id, TaskStatus::Verified);
+        let prev = task_manager.transit_task(&task_id, TaskStatus::Settled);
+
+        assert_eq!(prev, TaskStatus::Verified);
+        assert_eq!(
+            task_manager.get_task_status(&task_id),
+            Some(TaskStatus::Settled)
+        );
+    }
+
+    #[test]
+    fn test_task_listing() {
+        let mut task_manager = TaskManager::new();
+        let (first, _) = task_manager.publish_task(1, accounts(0), task_config());
+        let (second, _) = task_manager.publish_task(1, accounts(1), task_config());
+        let (third, _) = task_manager.publish_task(1, accounts(0), task_config());
+
+        let ids = |tasks: Vec<OnChainTaskConfig>| {
+            tasks.into_iter().map(|task| task.id).collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(task_manager.list_tasks(1, 1, 10)), vec![second.clone(), third.clone()]);
+        assert_eq!(ids(task_manager.list_tasks(2, 0, 10)), vec![]);
+        assert_eq!(
+            ids(task_manager.list_tasks_by_owner(&accounts(0), 0, 10)),
+            vec![first.clone(), third]
+        );
+        assert_eq!(
+            ids(task_manager.list_tasks_by_owner(&accounts(0), 0, 1)),
+            vec![first.clone()]
+        );
+
+        task_manager.claim_task(&second, accounts(2), None);
+        task_manager.claim_task(&first, accounts(2), None);
+
+        assert_eq!(
+            ids(task_manager.list_tasks_by_worker(&accounts(2), 0, 10)),
+            vec![second, first]
+        );
+        assert!(task_manager.query_specific_task(1, 3).is_none());
+    }
+
+    #[test]
+    fn test_claim_timeout() {
+        testing_env!(VMContextBuilder::new().block_timestamp(1_000_000_000).build());
+
+        let mut task_manager = TaskManager::new();
+        let (task_id, _) = task_manager.publish_task(
+            1,
+            accounts(0),
+            TaskConfig {
+                claim_deadline: Some(1_500.into()),
+                execution_timeout: Some(RoundDuration::new(500)),
+                ..task_config()
+            },
+        );
+
+        assert_eq!(task_manager.get_claim_timeout(&task_id), None);
+
+        task_manager.claim_task(&task_id, accounts(1), None);
+        assert_eq!(task_manager.get_claim_timeout(&task_id), Some(1_500.into()));
+        assert!(!task_manager.is_claim_timed_out(&task_id));
+
+        testing_env!(VMContextBuilder::new().block_timestamp(1_501_000_000).build());
+        assert!(task_manager.is_claim_timed_out(&task_id));
+        assert!(task_manager.is_claim_deadline_passed(&task_id));
+
+        // Publishing the task again drops the timed out claim.
+        task_manager.transit_task(&task_id, TaskStatus::Published);
+        assert!(task_manager.get_open_workers(&task_id).is_empty());
+        assert!(task_manager.get_task_state(&task_id).unwrap().attempts.is_empty());
+    }
+
+    #[test]
+    fn test_replicated_task_quorum() {
+        let mut task_manager = TaskManager::new();
+        let (task_id, _) = task_manager.publish_task(
+            1,
+            accounts(0),
+            TaskConfig {
+                replicas: 3,
+                quorum: 2,
+                ..task_config()
+            },
+        );
+
+        for worker in 1..=3 {
+            task_manager.claim_task(&task_id, accounts(worker), None);
+        }
+
+        let submit = |task_manager: &mut TaskManager, worker: usize, digest: &str| {
+            task_manager.submit_task_result(
+                &task_id,
+                accounts(worker),
+                "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+                digest.to_string(),
+            );
+        };
+
+        submit(&mut task_manager, 1, "a");
+        submit(&mut task_manager, 2, "b");
+        assert!(matches!(task_manager.check_quorum(&task_id), QuorumOutcome::Pending));
+        assert_eq!(task_manager.get_task_status(&task_id), Some(TaskStatus::Claimed));
+
+        submit(&mut task_manager, 3, "a");
+
+        let QuorumOutcome::Reached { digest, dissenters } = task_manager.check_quorum(&task_id) else {
+            panic!("quorum should be reached");
+        };
+        assert_eq!(digest, "a");
+        assert_eq!(dissenters, vec![(accounts(2), "b".to_string())]);
+
+        task_manager.record_consensus(&task_id, digest, vec![accounts(2)]);
+
+        let rewarded: Vec<AccountId> = task_manager
+            .get_rewarded_attempts(&task_id)
+            .into_iter()
+            .map(|attempt| attempt.worker.clone())
+            .collect();
+        assert_eq!(rewarded, vec![accounts(1), accounts(3)]);
+        assert_eq!(
+            task_manager.get_challenge_deadline(&task_id, RoundDuration::new(1_000)),
+            Some(1_000.into())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:307")]
+    fn test_replicas_exhausted() {
+        let mut task_manager = TaskManager::new();
+        let (task_id, _) = task_manager.publish_task(
+            1,
+            accounts(0),
+            TaskConfig {
+                replicas: 2,
+                quorum: 2,
+                ..task_config()
+            },
+        );
+
+        task_manager.claim_task(&task_id, accounts(1), None);
+        task_manager.claim_task(&task_id, accounts(2), None);
+        task_manager.claim_task(&task_id, accounts(3), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:301: Task cannot transit (from Published to Submitted)")]
+    fn test_invalid_task_transition() {
+        let mut task_manager = TaskManager::new();
+        let (task_id, _) = task_manager.publish_task(1, accounts(0), task_config());
+
+        task_manager.transit_task(&task_id, TaskStatus::Submitted);
+    }
+
+    #[test]
+    fn test_task_expiry_and_cancellation() {
+        let mut task_manager = TaskManager::new();
+        let (cancelled, _) = task_manager.publish_task(1, accounts(0), task_config());
+        let (rejected, _) = task_manager.publish_task(1, accounts(0), task_config());
+
+        task_manager.transit_task(&cancelled, TaskStatus::Cancelled);
+        assert!(task_manager.get_task_status(&cancelled).unwrap().is_terminal());
+
+        task_manager.claim_task(&rejected, accounts(1), None);
+        task_manager.submit_task_result(
+            &rejected,
+            accounts(1),
+            "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+            "digest".to_string(),
+        );
+        task_manager.transit_task(&rejected, TaskStatus::Rejected);
+        let prev = task_manager.transit_task(&rejected, TaskStatus::Expired);
+
+        assert_eq!(prev, TaskStatus::Rejected);
+        assert_eq!(task_manager.get_task_status(&rejected), Some(TaskStatus::Expired));
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:301: Task cannot transit (from Cancelled to Claimed)")]
+    fn test_claim_cancelled_task() {
+        let mut task_manager = TaskManager::new();
+        let (task_id, _) = task_manager.publish_task(1, accounts(0), task_config());
+
+        task_manager.transit_task(&task_id, TaskStatus::Cancelled);
+        task_manager.claim_task(&task_id, accounts(1), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:306")]
+    fn test_submit_without_claim() {
+        let mut task_manager = TaskManager::new();
+        let (task_id, _) = task_manager.publish_task(1, accounts(0), task_config());
+
+        task_manager.claim_task(&task_id, accounts(1), None);
+        task_manager.submit_task_result(
+            &task_id,
+            accounts(2),
+            "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+            "digest".to_string(),
+        );
+    }
+}
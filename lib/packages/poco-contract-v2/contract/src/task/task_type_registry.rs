@@ -0,0 +1,92 @@
+// `store::UnorderedMap` is deprecated in near-sdk 5 without a replacement yet.
+#![allow(deprecated)]
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::store::UnorderedMap;
+use poco_types::types::task::task_type::TaskType;
+
+/// Task types workers know how to execute, keyed by name.
+/// Only tasks of a registered type can be published.
+#[derive(BorshDeserialize, BorshSerialize)]
+#[borsh(crate = "near_sdk::borsh")]
+pub struct TaskTypeRegistry {
+    types: UnorderedMap<String, TaskType>,
+}
+
+impl TaskTypeRegistry {
+    pub fn new(types: Vec<TaskType>) -> Self {
+        let mut registry = TaskTypeRegistry {
+            types: UnorderedMap::new(b"task-type-registry:types".to_vec()),
+        };
+
+        for task_type in types {
+            registry.register(task_type);
+        }
+
+        registry
+    }
+
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&TaskType> {
+        self.types.get(name)
+    }
+
+    #[inline]
+    pub fn is_registered(&self, name: &str) -> bool {
+        self.types.contains_key(name)
+    }
+
+    #[inline]
+    pub fn list(&self, from: u32, limit: u32) -> Vec<TaskType> {
+        self.types
+            .values()
+            .skip(from as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect()
+    }
+
+    /// Adds the task type or replaces the entry of the same name, returning the replaced one.
+    pub fn register(&mut self, task_type: TaskType) -> Option<TaskType> {
+        self.types.insert(task_type.name.clone(), task_type)
+    }
+
+    pub fn unregister(&mut self, name: &str) -> Option<TaskType> {
+        self.types.remove(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task_type(name: &str, version: u32) -> TaskType {
+        TaskType {
+            name: name.to_string(),
+            version,
+            description: format!("{name} tasks"),
+            schema_cid: "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_register_and_unregister() {
+        let mut registry = TaskTypeRegistry::new(vec![task_type("MEDIA_TRANSCODING", 1)]);
+
+        assert!(registry.is_registered("MEDIA_TRANSCODING"));
+        assert!(!registry.is_registered("IMAGE_RESIZING"));
+
+        assert_eq!(registry.register(task_type("IMAGE_RESIZING", 1)), None);
+        assert_eq!(
+            registry.register(task_type("IMAGE_RESIZING", 2)),
+            Some(task_type("IMAGE_RESIZING", 1))
+        );
+        assert_eq!(registry.get("IMAGE_RESIZING").map(|e| e.version), Some(2));
+        assert_eq!(registry.list(0, 10).len(), 2);
+        assert_eq!(registry.list(1, 10).len(), 1);
+
+        assert!(registry.unregister("MEDIA_TRANSCODING").is_some());
+        assert!(registry.unregister("MEDIA_TRANSCODING").is_none());
+        assert!(!registry.is_registered("MEDIA_TRANSCODING"));
+    }
+}
@@ -1,39 +1,194 @@
-use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::collections::UnorderedMap;
-use near_sdk::store::LookupMap;
+use near_sdk::store::{LookupMap, Vector};
 use near_sdk::{AccountId, NearToken};
-use poco_types::types::user::{InternalUserProfile, UserProfile};
+use poco_types::types::error::PocoError;
+use poco_types::types::round::RoundId;
+use poco_types::types::task::TaskRequirement;
+use poco_types::types::uint::U256;
+use poco_types::types::user::{
+    validate_property_name, EndpointProtocol, InternalUserProfile, Reputation, UnbondingStake, UserProfile,
+    MAX_USER_PROPERTIES, REPUTATION_PROPERTY, STAKE_PROPERTY,
+};
+
+use crate::util::ensure;
 
 // for ease of testing
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 
 #[derive(BorshDeserialize, BorshSerialize)]
 #[borsh(crate = "near_sdk::borsh")]
 pub struct UserManager {
     user_map: LookupMap<AccountId, InternalUserProfile>,
+    /// Every account with a profile, in the order the profiles were created.
+    users: Vector<AccountId>,
     stake_map: UnorderedMap<AccountId, NearToken>,
+    unbonding_map: LookupMap<AccountId, UnbondingStake>,
 }
 
 impl UserManager {
     pub fn new() -> Self {
         UserManager {
             user_map: LookupMap::new(b"user-manager:usermap".to_vec()),
+            users: Vector::new(b"user-manager:users".to_vec()),
             stake_map: UnorderedMap::new(b"user-manager:stakemap".to_vec()),
+            unbonding_map: LookupMap::new(b"user-manager:unbondingmap".to_vec()),
         }
     }
 
     #[inline]
-    pub fn get_user_profile(&self, account: &AccountId) -> UserProfile {
-        self.user_map.get(account).map(|e| e.into()).unwrap()
+    pub fn get_user_profile(&self, account: &AccountId) -> Option<UserProfile<'_>> {
+        self.user_map.get(account).map(|e| e.into())
+    }
+
+    #[inline]
+    pub fn count_users(&self) -> u32 {
+        self.users.len()
+    }
+
+    pub fn list_users(&self, from: u32, limit: u32) -> Vec<AccountId> {
+        self.users
+            .iter()
+            .skip(from as usize)
+            .take(limit as usize)
+            .cloned()
+            .collect()
+    }
+
+    /// Accounts among the `limit` directory entries starting at `from` that meet every
+    /// requirement. A page may hold fewer matches than `limit`, or none.
+    pub fn find_users_matching(
+        &self,
+        requirements: &[TaskRequirement],
+        from: u32,
+        limit: u32,
+    ) -> Vec<AccountId> {
+        self.users
+            .iter()
+            .skip(from as usize)
+            .take(limit as usize)
+            .filter(|account| self.meets_requirements(account, requirements))
+            .cloned()
+            .collect()
     }
 
     #[inline]
     pub fn get_user_stake(&self, account: &AccountId) -> NearToken {
-        self.stake_map.get(account).unwrap().clone()
+        self.stake_map
+            .get(account)
+            .unwrap_or(NearToken::from_yoctonear(0))
+    }
+
+    #[inline]
+    pub fn get_reputation(&self, account: &AccountId) -> Reputation {
+        self.user_map
+            .get(account)
+            .map(|profile| profile.get_reputation().clone())
+            .unwrap_or_default()
+    }
+
+    /// Records the outcome of a task the account worked on and returns its updated reputation.
+    pub fn record_outcome(&mut self, account: &AccountId, success: bool) -> Reputation {
+        self.get_or_create_profile(account)
+            .record_outcome(success)
+            .clone()
+    }
+
+    #[inline]
+    pub fn get_unbonding_stake(&self, account: &AccountId) -> Option<&UnbondingStake> {
+        self.unbonding_map.get(account)
+    }
+
+    /// Adds `amount` to the account's stake and returns the new stake. Staking creates
+    /// a profile, so every staker shows up in the user directory.
+    pub fn stake(&mut self, account: &AccountId, amount: NearToken) -> NearToken {
+        self.get_or_create_profile(account);
+
+        let stake = self.get_user_stake(account).saturating_add(amount);
+
+        self.stake_map.insert(account, &stake);
+
+        stake
+    }
+
+    /// Moves `amount` of the stake into unbonding until `unlock_round`, merging it
+    /// with any stake that is already unbonding. Returns the remaining stake.
+    pub fn unstake(&mut self, account: &AccountId, amount: NearToken, unlock_round: RoundId) -> NearToken {
+        let stake = self.get_user_stake(account);
+
+        ensure!(amount <= stake, PocoError::InsufficientStake);
+
+        let stake = stake.saturating_sub(amount);
+        self.stake_map.insert(account, &stake);
+
+        let unbonding = self
+            .unbonding_map
+            .get(account)
+            .map(|unbonding| unbonding.amount)
+            .unwrap_or_default();
+
+        self.unbonding_map.insert(
+            account.clone(),
+            UnbondingStake {
+                amount: unbonding + U256::from(amount.as_yoctonear()),
+                unlock_round,
+            },
+        );
+
+        stake
+    }
+
+    /// Removes the unbonded stake of the account once `current_round` reaches its unlock round.
+    pub fn withdraw_unstaked(&mut self, account: &AccountId, current_round: RoundId) -> NearToken {
+        let unbonding = self
+            .unbonding_map
+            .get(account)
+            .unwrap_or_else(|| PocoError::NothingToWithdraw.panic());
+
+        ensure!(
+            current_round >= unbonding.unlock_round,
+            PocoError::StakeUnbonding
+        );
+
+        let amount = NearToken::from_yoctonear(unbonding.amount.as_u128());
+        self.unbonding_map.remove(account);
+
+        amount
+    }
+
+    /// Takes up to `amount` from the account's stake, then from its unbonding stake so
+    /// unstaking cannot dodge a slash. Returns the amount taken and the remaining stake.
+    pub fn slash(&mut self, account: &AccountId, amount: NearToken) -> (NearToken, NearToken) {
+        let stake = self.get_user_stake(account);
+        let mut slashed = amount.min(stake);
+        let stake = stake.saturating_sub(slashed);
+
+        self.stake_map.insert(account, &stake);
+
+        if let Some(unbonding) = self.unbonding_map.get(account).cloned() {
+            let remaining = U256::from(amount.saturating_sub(slashed).as_yoctonear());
+            let taken = remaining.min(unbonding.amount);
+
+            if taken == unbonding.amount {
+                self.unbonding_map.remove(account);
+            } else {
+                self.unbonding_map.insert(
+                    account.clone(),
+                    UnbondingStake {
+                        amount: unbonding.amount - taken,
+                        ..unbonding
+                    },
+                );
+            }
+
+            slashed = slashed.saturating_add(NearToken::from_yoctonear(taken.as_u128()));
+        }
+
+        (slashed, stake)
     }
 
     #[inline]
@@ -42,27 +197,102 @@ impl UserManager {
         self.stake_map.insert(account, &stake_token);
     }
 
+    pub fn set_user_endpoint(&mut self, account: &AccountId, protocol: EndpointProtocol, url: String) {
+        if let Err(error) = protocol.validate(&url) {
+            error.panic_with(&url);
+        }
+
+        self.get_or_create_profile(account).set_endpoint(protocol, url);
+    }
+
     #[inline]
-    pub fn set_user_endpoint(&mut self, account: &AccountId, endpoint: String) {
-        if self.user_map.contains_key(account) {
-            self.user_map
-                .get_mut(account)
-                .unwrap()
-                .set_endpoint(endpoint);
-        } else {
-            let mut profile = InternalUserProfile::new(account);
+    pub fn remove_user_endpoint(&mut self, account: &AccountId, protocol: EndpointProtocol) -> Option<String> {
+        self.user_map
+            .get_mut(account)
+            .and_then(|profile| profile.remove_endpoint(protocol))
+    }
+
+    /// Sets properties declared by the user. Built-in properties cannot be overridden.
+    pub fn set_user_props(&mut self, account: &AccountId, props: &[(String, U256)]) {
+        for (name, value) in props {
+            if let Err(error) = validate_property_name(name) {
+                error.panic_with(name);
+            }
+
+            let profile = self.get_or_create_profile(account);
+
+            ensure!(
+                profile.get_prop(name).is_some() || profile.prop_count() < MAX_USER_PROPERTIES,
+                PocoError::TooManyProperties
+            );
+
+            profile.set_prop(name, value);
+        }
+    }
+
+    /// Removes a property declared by the user, returning its last value.
+    pub fn remove_user_prop(&mut self, account: &AccountId, name: &str) -> Option<U256> {
+        if let Err(error) = validate_property_name(name) {
+            error.panic_with(name);
+        }
+
+        self.user_map
+            .get_mut(account)
+            .and_then(|profile| profile.remove_prop(name))
+    }
 
-            profile.set_endpoint(endpoint);
+    /// Writes the cached profile changes to storage, so that the storage they use can be
+    /// measured before the call ends.
+    pub fn flush(&mut self) {
+        self.user_map.flush();
+        self.users.flush();
+    }
 
-            self.user_map.insert(account.clone(), profile);
+    fn get_or_create_profile(&mut self, account: &AccountId) -> &mut InternalUserProfile {
+        if !self.user_map.contains_key(account) {
+            self.user_map
+                .insert(account.clone(), InternalUserProfile::new(account));
+            self.users.push(account.clone());
         }
+
+        self.user_map.get_mut(account).unwrap()
+    }
+
+    #[inline]
+    pub fn get_user_endpoint(&self, account: &AccountId, protocol: EndpointProtocol) -> Option<&str> {
+        self.user_map
+            .get(account)
+            .and_then(|e| e.get_endpoint(protocol).map(|e| e.as_str()))
     }
 
     #[inline]
-    pub fn get_user_endpoint(&self, account: &AccountId) -> Option<&str> {
+    pub fn get_user_endpoints(&self, account: &AccountId) -> BTreeMap<EndpointProtocol, &str> {
         self.user_map
             .get(account)
-            .and_then(|e| e.get_endpoint().as_ref().map(|e| e.as_str()))
+            .map(|profile| profile.get_endpoints())
+            .unwrap_or_default()
+    }
+
+    /// Resolves a property of the account, built-in properties such as `stake` first.
+    #[inline]
+    pub fn get_user_prop(&self, account: &AccountId, name: &str) -> Option<U256> {
+        match name {
+            STAKE_PROPERTY => Some(U256::from(self.get_user_stake(account).as_yoctonear())),
+            REPUTATION_PROPERTY => Some(U256::from(self.get_reputation(account).score)),
+            _ => self
+                .user_map
+                .get(account)
+                .and_then(|profile| profile.get_prop(name).copied()),
+        }
+    }
+
+    /// Whether every requirement holds for the account. A missing property never matches.
+    pub fn meets_requirements(&self, account: &AccountId, requirements: &[TaskRequirement]) -> bool {
+        requirements.iter().all(|requirement| {
+            self.get_user_prop(account, &requirement.property)
+                .map(|value| requirement.is_ok(&value))
+                .unwrap_or(false)
+        })
     }
 
     #[inline]
@@ -103,12 +333,13 @@ impl Default for UserManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use poco_types::types::task::TaskRequirementOperator;
     use std::path::PathBuf;
 
     #[test]
     fn test_load_stake_map_from_file() {
         // Create a temporary file and write some data to it
-        let test_file_path = PathBuf::from("/home/sole/PoCo/lib/packages/poco-contract-v2/contract/temp-file/stake.txt");
+        let test_file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("temp-file/stake.txt");
 
         // Create a UserManager instance
         let mut user_manager = UserManager::new();
@@ -138,7 +369,6 @@ mod tests {
         // let stake_token = NearToken::from_yoctonear(stake);
         // self.stake_map.insert(account, &stake_token);
         user_manager.set_user_stake(&account, stake);
-        let stake = 70_u128;
         println!("{:?}", user_manager.get_user_stake(&account));
         assert_eq!(
             user_manager.get_user_stake(&account),
@@ -146,6 +376,253 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_stake_unstake_and_withdraw() {
+        let mut user_manager = UserManager::new();
+        let user = AccountId::try_from("user1".to_string()).unwrap();
+
+        user_manager.stake(&user, NearToken::from_yoctonear(100));
+        let stake = user_manager.unstake(&user, NearToken::from_yoctonear(40), 3);
+
+        assert_eq!(stake, NearToken::from_yoctonear(60));
+        assert_eq!(
+            user_manager.get_user_prop(&user, STAKE_PROPERTY),
+            Some(U256::from(60))
+        );
+        assert_eq!(
+            user_manager.withdraw_unstaked(&user, 3),
+            NearToken::from_yoctonear(40)
+        );
+        assert!(user_manager.get_unbonding_stake(&user).is_none());
+    }
+
+    #[test]
+    fn test_unbonding_merges() {
+        let mut user_manager = UserManager::new();
+        let user = AccountId::try_from("user1".to_string()).unwrap();
+
+        user_manager.stake(&user, NearToken::from_yoctonear(100));
+        user_manager.unstake(&user, NearToken::from_yoctonear(30), 3);
+        let stake = user_manager.unstake(&user, NearToken::from_yoctonear(20), 4);
+
+        assert_eq!(stake, NearToken::from_yoctonear(50));
+
+        // Unstaking again pushes the unlock round of everything unbonding.
+        let unbonding = user_manager.get_unbonding_stake(&user).unwrap();
+        assert_eq!(unbonding.amount, U256::from(50));
+        assert_eq!(unbonding.unlock_round, 4);
+
+        assert_eq!(
+            user_manager.withdraw_unstaked(&user, 5),
+            NearToken::from_yoctonear(50)
+        );
+        assert_eq!(user_manager.get_user_stake(&user), NearToken::from_yoctonear(50));
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:401")]
+    fn test_unstake_more_than_stake() {
+        let mut user_manager = UserManager::new();
+        let user = AccountId::try_from("user1".to_string()).unwrap();
+
+        user_manager.stake(&user, NearToken::from_yoctonear(100));
+        user_manager.unstake(&user, NearToken::from_yoctonear(101), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:502")]
+    fn test_withdraw_without_unbonding() {
+        let mut user_manager = UserManager::new();
+        let user = AccountId::try_from("user1".to_string()).unwrap();
+
+        user_manager.stake(&user, NearToken::from_yoctonear(100));
+        user_manager.withdraw_unstaked(&user, 3);
+    }
+
+    #[test]
+    fn test_reputation_property() {
+        let mut user_manager = UserManager::new();
+        let user = AccountId::try_from("user1".to_string()).unwrap();
+
+        assert_eq!(
+            user_manager.get_user_prop(&user, REPUTATION_PROPERTY),
+            Some(U256::from(5_000))
+        );
+
+        user_manager.record_outcome(&user, true);
+        let reputation = user_manager.record_outcome(&user, false);
+
+        assert_eq!((reputation.completed, reputation.failed), (1, 1));
+        assert_eq!(
+            user_manager.get_user_prop(&user, REPUTATION_PROPERTY),
+            Some(U256::from(reputation.score))
+        );
+    }
+
+    #[test]
+    fn test_user_props() {
+        let mut user_manager = UserManager::new();
+        let user = AccountId::try_from("user1".to_string()).unwrap();
+
+        user_manager.set_user_props(
+            &user,
+            &[
+                ("cpu_cores".to_string(), U256::from(8)),
+                ("h265_fps".to_string(), U256::from(60)),
+            ],
+        );
+
+        assert_eq!(user_manager.get_user_prop(&user, "cpu_cores"), Some(U256::from(8)));
+        assert_eq!(
+            user_manager.remove_user_prop(&user, "h265_fps"),
+            Some(U256::from(60))
+        );
+        assert_eq!(user_manager.get_user_prop(&user, "h265_fps"), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:402")]
+    fn test_set_reserved_prop() {
+        let mut user_manager = UserManager::new();
+        let user = AccountId::try_from("user1".to_string()).unwrap();
+
+        user_manager.set_user_props(&user, &[(STAKE_PROPERTY.to_string(), U256::from(1))]);
+    }
+
+    #[test]
+    fn test_user_directory() {
+        let mut user_manager = UserManager::new();
+        let user1 = AccountId::try_from("user1".to_string()).unwrap();
+        let user2 = AccountId::try_from("user2".to_string()).unwrap();
+
+        assert!(user_manager.get_user_profile(&user1).is_none());
+
+        user_manager.stake(&user1, NearToken::from_yoctonear(100));
+        user_manager.set_user_props(&user2, &[("cpu_cores".to_string(), U256::from(8))]);
+        user_manager.set_user_props(&user1, &[("cpu_cores".to_string(), U256::from(4))]);
+
+        assert_eq!(user_manager.count_users(), 2);
+        assert_eq!(user_manager.list_users(0, 10), vec![user1.clone(), user2.clone()]);
+        assert_eq!(user_manager.list_users(1, 10), vec![user2.clone()]);
+        assert!(user_manager.get_user_profile(&user1).is_some());
+
+        let requirement = |property: &str, value: u64| TaskRequirement {
+            property: property.to_string(),
+            operator: TaskRequirementOperator::GreaterThanOrEqual,
+            value: U256::from(value),
+        };
+
+        assert_eq!(
+            user_manager.find_users_matching(&[requirement("cpu_cores", 6)], 0, 10),
+            vec![user2.clone()]
+        );
+        assert!(user_manager
+            .find_users_matching(&[requirement("cpu_cores", 6)], 0, 1)
+            .is_empty());
+        assert_eq!(
+            user_manager.find_users_matching(&[requirement("cpu_cores", 6)], 1, 1),
+            vec![user2]
+        );
+        assert_eq!(
+            user_manager.find_users_matching(
+                &[requirement("cpu_cores", 2), requirement(STAKE_PROPERTY, 1)],
+                0,
+                10,
+            ),
+            vec![user1]
+        );
+    }
+
+    #[test]
+    fn test_user_endpoints() {
+        let mut user_manager = UserManager::new();
+        let user = AccountId::try_from("user1".to_string()).unwrap();
+
+        user_manager.set_user_endpoint(
+            &user,
+            EndpointProtocol::Http,
+            "https://worker.example.com".to_string(),
+        );
+        user_manager.set_user_endpoint(
+            &user,
+            EndpointProtocol::Grpc,
+            "grpc://worker.example.com:50051".to_string(),
+        );
+
+        assert_eq!(
+            user_manager.get_user_endpoint(&user, EndpointProtocol::Http),
+            Some("https://worker.example.com")
+        );
+        assert_eq!(user_manager.get_user_endpoints(&user).len(), 2);
+        assert_eq!(
+            user_manager.remove_user_endpoint(&user, EndpointProtocol::Grpc),
+            Some("grpc://worker.example.com:50051".to_string())
+        );
+        assert_eq!(user_manager.get_user_endpoint(&user, EndpointProtocol::Grpc), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:405")]
+    fn test_set_invalid_endpoint() {
+        let mut user_manager = UserManager::new();
+        let user = AccountId::try_from("user1".to_string()).unwrap();
+
+        user_manager.set_user_endpoint(
+            &user,
+            EndpointProtocol::Ipfs,
+            "https://worker.example.com".to_string(),
+        );
+    }
+
+    #[test]
+    fn test_slash() {
+        let mut user_manager = UserManager::new();
+        let user = AccountId::try_from("user1".to_string()).unwrap();
+
+        user_manager.stake(&user, NearToken::from_yoctonear(100));
+
+        assert_eq!(
+            user_manager.slash(&user, NearToken::from_yoctonear(30)),
+            (NearToken::from_yoctonear(30), NearToken::from_yoctonear(70))
+        );
+        assert_eq!(
+            user_manager.slash(&user, NearToken::from_yoctonear(100)),
+            (NearToken::from_yoctonear(70), NearToken::from_yoctonear(0))
+        );
+    }
+
+    #[test]
+    fn test_slash_unbonding_stake() {
+        let mut user_manager = UserManager::new();
+        let user = AccountId::try_from("user1".to_string()).unwrap();
+
+        user_manager.stake(&user, NearToken::from_yoctonear(100));
+        user_manager.unstake(&user, NearToken::from_yoctonear(80), 3);
+
+        assert_eq!(
+            user_manager.slash(&user, NearToken::from_yoctonear(50)),
+            (NearToken::from_yoctonear(50), NearToken::from_yoctonear(0))
+        );
+        assert_eq!(user_manager.get_unbonding_stake(&user).unwrap().amount, U256::from(50));
+
+        assert_eq!(
+            user_manager.slash(&user, NearToken::from_yoctonear(100)),
+            (NearToken::from_yoctonear(50), NearToken::from_yoctonear(0))
+        );
+        assert!(user_manager.get_unbonding_stake(&user).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "POCO_ERROR:501: Stake is still unbonding")]
+    fn test_withdraw_before_unlock() {
+        let mut user_manager = UserManager::new();
+        let user = AccountId::try_from("user1".to_string()).unwrap();
+
+        user_manager.stake(&user, NearToken::from_yoctonear(100));
+        user_manager.unstake(&user, NearToken::from_yoctonear(40), 3);
+        user_manager.withdraw_unstaked(&user, 2);
+    }
+
     #[test]
     fn test_user_stake() {
         let mut user_manager = UserManager::new();
@@ -1,3 +1,14 @@
 pub use queue::CircularQueue;
 
 mod queue;
+
+/// Aborts the call with the encoded `PocoError` unless the condition holds.
+macro_rules! ensure {
+    ($cond:expr, $error:expr $(,)?) => {
+        if !$cond {
+            $error.panic()
+        }
+    };
+}
+
+pub(crate) use ensure;
@@ -6,7 +6,8 @@ use near_sdk::IntoStorageKey;
 
 mod iter;
 
-#[derive(Debug)]
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+#[borsh(crate = "near_sdk::borsh")]
 pub struct CircularQueue<T>
 where
     T: BorshDeserialize + BorshSerialize,
@@ -67,6 +68,12 @@ where
         self.offset += 1;
     }
 
+    pub fn clear(&mut self) {
+        self.buf.clear();
+        self.head = 0;
+        self.tail = 0;
+    }
+
     pub fn remove_back(&mut self) {
         if self.is_empty() {
             return;
@@ -90,6 +97,10 @@ where
         self.tail = (self.tail + 1) % self.length;
     }
 
+    pub fn front(&self) -> Option<&T> {
+        self.get(self.offset)
+    }
+
     pub fn get(&self, index: u64) -> Option<&T> {
         if index < self.offset || index >= self.offset + self.len() {
             return None;
@@ -120,11 +131,11 @@ where
         Some(self.buf.replace(index as u32, element))
     }
 
-    pub fn iter(&self) -> iter::Iter<T> {
+    pub fn iter(&self) -> iter::Iter<'_, T> {
         iter::Iter::new(self)
     }
 
-    pub fn iter_mut(&mut self) -> iter::IterMut<T> {
+    pub fn iter_mut(&mut self) -> iter::IterMut<'_, T> {
         iter::IterMut::new(self)
     }
 }
@@ -23,8 +23,19 @@ else
 fi
 
 
+# Round length differs between networks, override it with POCO_ROUND_DURATION_IN_MS
+POCO_ROUND_DURATION_IN_MS=${POCO_ROUND_DURATION_IN_MS:-1800000}
+# Task types registered at deployment, MEDIA_TRANSCODING is only registered once the CID of its
+# config schema is given with POCO_MEDIA_TRANSCODING_SCHEMA_CID
+if [ -n "$POCO_MEDIA_TRANSCODING_SCHEMA_CID" ]; then
+  POCO_TASK_TYPES="[{\"name\": \"MEDIA_TRANSCODING\", \"version\": 1, \"description\": \"Transcode media files\", \"schema_cid\": \"${POCO_MEDIA_TRANSCODING_SCHEMA_CID}\"}]"
+else
+  POCO_TASK_TYPES="[]"
+fi
+INIT_ARGS="{\"owner\": \"${NEAR_HELPER_ACCOUNT}\", \"round_duration\": {\"duration_in_ms\": ${POCO_ROUND_DURATION_IN_MS}}, \"task_types\": ${POCO_TASK_TYPES}}"
+
 # https://docs.near.org/tools/near-cli#near-dev-deploy
-OUTPUT=$(echo "y" | $NEAR_DEPLOY_COMMAND --wasmFile ${WORKSPACE_DIR}/contract/target/near/poco.wasm --accountId ${NEAR_HELPER_ACCOUNT})
+OUTPUT=$(echo "y" | $NEAR_DEPLOY_COMMAND --wasmFile ${WORKSPACE_DIR}/contract/target/near/poco.wasm --accountId ${NEAR_HELPER_ACCOUNT} --initFunction new --initArgs "${INIT_ARGS}")
 CONTRACT_ID=$(echo $OUTPUT | awk '{ print $NF }')
 
 echo $OUTPUT
@@ -0,0 +1,53 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::schemars::JsonSchema;
+
+use crate::types::round::{RoundDuration, RoundId};
+use crate::types::uint::U256;
+
+pub const DEFAULT_MAX_CONFIG_BYTES: u32 = 4096;
+pub const DEFAULT_UNBONDING_ROUNDS: RoundId = 2;
+pub const DEFAULT_EVENT_RETENTION: u32 = 10_000;
+pub const DEFAULT_ASSIGNED_WORKERS: u32 = 3;
+pub const DEFAULT_ASSIGNED_VERIFIERS: u32 = 1;
+pub const DEFAULT_CHALLENGE_WINDOW_IN_MS: u64 = 10 * 60 * 1000;
+pub const DEFAULT_DISPUTE_TIMEOUT_IN_MS: u64 = 60 * 60 * 1000;
+pub const DEFAULT_MIN_DISPUTE_COMMITTEE: u32 = 1;
+pub const DEFAULT_VERIFICATION_TIMEOUT_IN_MS: u64 = 60 * 60 * 1000;
+
+/// Protocol parameters chosen at deployment, most of them adjustable by the owner afterwards.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[borsh(crate = "near_sdk::borsh")]
+#[schemars(crate = "near_sdk::schemars")]
+pub struct ContractConfig {
+    /// Duration of rounds started from now on; the running round keeps its own.
+    pub round_duration: RoundDuration,
+    /// Stake a worker needs before it may claim tasks.
+    pub min_stake: U256,
+    /// Upper bound of the encoded `config` of a task.
+    pub max_config_bytes: u32,
+    pub unbonding_rounds: RoundId,
+    /// Number of most recent events kept on chain, fixed at deployment.
+    pub event_retention: u32,
+    /// Number of stakers drawn to work on each task, 0 leaves tasks open to every staker.
+    pub assigned_workers: u32,
+    /// Number of verifiers drawn for each task, 0 lets any verifier verify it.
+    pub assigned_verifiers: u32,
+    /// Time after the accepted result of a task was submitted during which it can be disputed
+    /// and the task cannot be settled.
+    pub challenge_window: RoundDuration,
+    /// Smallest bond a challenger has to attach to a dispute.
+    pub min_dispute_bond: U256,
+    /// Stake slashed from a worker whose result disagreed with the quorum of a replicated task.
+    pub dissent_slash: U256,
+    /// Time the verifiers have to resolve a dispute, after which it expires.
+    pub dispute_timeout: RoundDuration,
+    /// Number of verifiers a task needs to have been drawn for its result to be disputable.
+    pub min_dispute_committee: u32,
+    /// Stake slashed from each worker of a result a dispute was upheld against.
+    pub dispute_slash: U256,
+    /// Time the verifiers of a task have to accept or reject a submitted result, after which
+    /// anyone may accept it in their place.
+    pub verification_timeout: RoundDuration,
+}
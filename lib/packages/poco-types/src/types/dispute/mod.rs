@@ -0,0 +1,82 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+use near_sdk::schemars::JsonSchema;
+
+use crate::types::round::BlockTimestamp;
+use crate::types::task::status::TaskStatus;
+use crate::types::uint::U256;
+
+#[derive(
+    BorshDeserialize,
+    BorshSerialize,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Debug,
+    PartialEq,
+    Eq,
+    Copy,
+    Clone,
+    strum::Display,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[borsh(crate = "near_sdk::borsh")]
+#[schemars(crate = "near_sdk::schemars")]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DisputeStatus {
+    Open,
+    /// The disputed result was wrong, its workers were slashed in favour of the challenger.
+    Upheld,
+    /// The disputed result stands, the challenger's bond went to its workers.
+    Dismissed,
+    /// The verifiers did not agree in time, the bond was refunded and the task restored.
+    Expired,
+}
+
+/// Digest a verifier got by executing the task again.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[borsh(crate = "near_sdk::borsh")]
+#[schemars(crate = "near_sdk::schemars")]
+pub struct DisputeVote {
+    pub verifier: AccountId,
+    pub digest: String,
+}
+
+/// A challenge of the accepted result of a task. The verifiers of the task execute it again
+/// and vote with their digest, once a majority agrees the dispute is upheld unless they
+/// reproduced `disputed_digest`. Without a majority before the dispute timeout, it expires.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[borsh(crate = "near_sdk::borsh")]
+#[schemars(crate = "near_sdk::schemars")]
+pub struct Dispute {
+    pub challenger: AccountId,
+    pub counter_digest: String,
+    pub bond: U256,
+    /// Workers that reported the disputed digest.
+    pub defendants: Vec<AccountId>,
+    pub disputed_digest: String,
+    /// Status of the task before it was disputed, restored when the dispute is dismissed.
+    pub prior_status: TaskStatus,
+    pub opened_at: BlockTimestamp,
+    pub votes: Vec<DisputeVote>,
+    pub status: DisputeStatus,
+}
+
+impl Dispute {
+    /// Digest `threshold` votes agree on, if any.
+    pub fn agreed_digest(&self, threshold: usize) -> Option<&str> {
+        self.votes
+            .iter()
+            .map(|vote| vote.digest.as_str())
+            .find(|digest| {
+                self.votes
+                    .iter()
+                    .filter(|vote| vote.digest == *digest)
+                    .count()
+                    >= threshold
+            })
+    }
+}
@@ -0,0 +1,282 @@
+use std::fmt::{Display, Formatter};
+
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::schemars::JsonSchema;
+
+/// Prefix of the panic messages the contract fails with, followed by the error code.
+pub const POCO_ERROR_PREFIX: &str = "POCO_ERROR:";
+
+/// Errors the contract fails with. The codes are part of the contract interface and
+/// must never be reused or renumbered, new errors get new codes within their group.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Copy, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "near_sdk::schemars")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PocoError {
+    // Access
+    Unauthorized,
+    ContractPaused,
+    OwnershipRevocation,
+    NotAssigned,
+
+    // Rounds
+    RoundNotRunning,
+    RoundStillRunning,
+    RoundNotFinalizing,
+
+    // Tasks
+    TaskNotFound,
+    InvalidTaskTransition,
+    InvalidTaskConfig,
+    ClaimDeadlinePassed,
+    ExecutionTimedOut,
+    ClaimNotTimedOut,
+    NoOpenAttempt,
+    TaskAlreadyClaimed,
+    BountyDecreased,
+    OwnerCannotClaim,
+    InvalidCid,
+    UnsupportedLinkScheme,
+    MissingOffer,
+    TaskConfigTooLarge,
+    UnknownTaskType,
+    TaskTypeVersionNotRaised,
+    InvalidReplication,
+    TaskResultTooLarge,
+    VerificationNotExpired,
+
+    // Requirements
+    RequirementUnmet,
+    InsufficientStake,
+    ReservedProperty,
+    InvalidPropertyName,
+    TooManyProperties,
+    InvalidEndpoint,
+
+    // Funds
+    InsufficientDeposit,
+    StakeUnbonding,
+    NothingToWithdraw,
+    AmountTooLarge,
+
+    // Disputes
+    ChallengeWindowClosed,
+    ChallengeWindowOpen,
+    DisputeAlreadyOpen,
+    NoOpenDispute,
+    AlreadyVoted,
+    ConflictOfInterest,
+    DisputeCommitteeTooSmall,
+    DisputeNotExpired,
+    DisputeExpired,
+}
+
+impl PocoError {
+    pub fn code(&self) -> u32 {
+        match self {
+            PocoError::Unauthorized => 100,
+            PocoError::ContractPaused => 101,
+            PocoError::OwnershipRevocation => 102,
+            PocoError::NotAssigned => 103,
+
+            PocoError::RoundNotRunning => 200,
+            PocoError::RoundStillRunning => 201,
+            PocoError::RoundNotFinalizing => 202,
+
+            PocoError::TaskNotFound => 300,
+            PocoError::InvalidTaskTransition => 301,
+            PocoError::InvalidTaskConfig => 302,
+            PocoError::ClaimDeadlinePassed => 303,
+            PocoError::ExecutionTimedOut => 304,
+            PocoError::ClaimNotTimedOut => 305,
+            PocoError::NoOpenAttempt => 306,
+            PocoError::TaskAlreadyClaimed => 307,
+            PocoError::BountyDecreased => 308,
+            PocoError::OwnerCannotClaim => 309,
+            PocoError::InvalidCid => 310,
+            PocoError::UnsupportedLinkScheme => 311,
+            PocoError::MissingOffer => 312,
+            PocoError::TaskConfigTooLarge => 313,
+            PocoError::UnknownTaskType => 314,
+            PocoError::TaskTypeVersionNotRaised => 315,
+            PocoError::InvalidReplication => 316,
+            PocoError::TaskResultTooLarge => 317,
+            PocoError::VerificationNotExpired => 318,
+
+            PocoError::RequirementUnmet => 400,
+            PocoError::InsufficientStake => 401,
+            PocoError::ReservedProperty => 402,
+            PocoError::InvalidPropertyName => 403,
+            PocoError::TooManyProperties => 404,
+            PocoError::InvalidEndpoint => 405,
+
+            PocoError::InsufficientDeposit => 500,
+            PocoError::StakeUnbonding => 501,
+            PocoError::NothingToWithdraw => 502,
+            PocoError::AmountTooLarge => 503,
+
+            PocoError::ChallengeWindowClosed => 600,
+            PocoError::ChallengeWindowOpen => 601,
+            PocoError::DisputeAlreadyOpen => 602,
+            PocoError::NoOpenDispute => 603,
+            PocoError::AlreadyVoted => 604,
+            PocoError::ConflictOfInterest => 605,
+            PocoError::DisputeCommitteeTooSmall => 606,
+            PocoError::DisputeNotExpired => 607,
+            PocoError::DisputeExpired => 608,
+        }
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        const ALL: &[PocoError] = &[
+            PocoError::Unauthorized,
+            PocoError::ContractPaused,
+            PocoError::OwnershipRevocation,
+            PocoError::NotAssigned,
+            PocoError::RoundNotRunning,
+            PocoError::RoundStillRunning,
+            PocoError::RoundNotFinalizing,
+            PocoError::TaskNotFound,
+            PocoError::InvalidTaskTransition,
+            PocoError::InvalidTaskConfig,
+            PocoError::ClaimDeadlinePassed,
+            PocoError::ExecutionTimedOut,
+            PocoError::ClaimNotTimedOut,
+            PocoError::NoOpenAttempt,
+            PocoError::TaskAlreadyClaimed,
+            PocoError::BountyDecreased,
+            PocoError::OwnerCannotClaim,
+            PocoError::InvalidCid,
+            PocoError::UnsupportedLinkScheme,
+            PocoError::MissingOffer,
+            PocoError::TaskConfigTooLarge,
+            PocoError::UnknownTaskType,
+            PocoError::TaskTypeVersionNotRaised,
+            PocoError::InvalidReplication,
+            PocoError::TaskResultTooLarge,
+            PocoError::VerificationNotExpired,
+            PocoError::RequirementUnmet,
+            PocoError::InsufficientStake,
+            PocoError::ReservedProperty,
+            PocoError::InvalidPropertyName,
+            PocoError::TooManyProperties,
+            PocoError::InvalidEndpoint,
+            PocoError::InsufficientDeposit,
+            PocoError::StakeUnbonding,
+            PocoError::NothingToWithdraw,
+            PocoError::AmountTooLarge,
+            PocoError::ChallengeWindowClosed,
+            PocoError::ChallengeWindowOpen,
+            PocoError::DisputeAlreadyOpen,
+            PocoError::NoOpenDispute,
+            PocoError::AlreadyVoted,
+            PocoError::ConflictOfInterest,
+            PocoError::DisputeCommitteeTooSmall,
+            PocoError::DisputeNotExpired,
+            PocoError::DisputeExpired,
+        ];
+
+        ALL.iter().copied().find(|error| error.code() == code)
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            PocoError::Unauthorized => "Caller is not allowed to do this",
+            PocoError::ContractPaused => "Contract is paused",
+            PocoError::OwnershipRevocation => "Owner cannot revoke its own ownership",
+            PocoError::NotAssigned => "Caller is not assigned to the task",
+            PocoError::RoundNotRunning => "Round is not running",
+            PocoError::RoundStillRunning => "Round has not ended yet",
+            PocoError::RoundNotFinalizing => "Round is not waiting for finalization",
+            PocoError::TaskNotFound => "Task not found",
+            PocoError::InvalidTaskTransition => "Task cannot transit",
+            PocoError::InvalidTaskConfig => "Task config is invalid",
+            PocoError::ClaimDeadlinePassed => "Task claim deadline has passed",
+            PocoError::ExecutionTimedOut => "Task execution has timed out",
+            PocoError::ClaimNotTimedOut => "Task claim has not timed out",
+            PocoError::NoOpenAttempt => "No open attempt for the worker",
+            PocoError::TaskAlreadyClaimed => "Task has already been claimed",
+            PocoError::BountyDecreased => "Task bounty can only be raised",
+            PocoError::OwnerCannotClaim => "Task owner cannot claim its own task",
+            PocoError::InvalidCid => "IPFS hash is not a valid CID",
+            PocoError::UnsupportedLinkScheme => "Link URL scheme is not allowed",
+            PocoError::MissingOffer => "Task has no offer",
+            PocoError::TaskConfigTooLarge => "Task config exceeds the size limit",
+            PocoError::UnknownTaskType => "Task type is not registered",
+            PocoError::TaskTypeVersionNotRaised => "Task type version can only be raised",
+            PocoError::InvalidReplication => "Task replicas or quorum are out of range",
+            PocoError::TaskResultTooLarge => "Task result output or digest exceeds the size limit",
+            PocoError::VerificationNotExpired => "Task verification deadline has not passed",
+            PocoError::RequirementUnmet => "Requirements are not met",
+            PocoError::InsufficientStake => "Stake is not enough",
+            PocoError::ReservedProperty => "Property is controlled by the contract",
+            PocoError::InvalidPropertyName => "Property name is invalid",
+            PocoError::TooManyProperties => "Profile has too many properties",
+            PocoError::InvalidEndpoint => "Endpoint is not a valid address for its protocol",
+            PocoError::InsufficientDeposit => "Attached deposit is not enough",
+            PocoError::StakeUnbonding => "Stake is still unbonding",
+            PocoError::NothingToWithdraw => "No unstaked balance to withdraw",
+            PocoError::AmountTooLarge => "Amount exceeds the largest token balance",
+            PocoError::ChallengeWindowClosed => "Challenge window of the task has closed",
+            PocoError::ChallengeWindowOpen => "Challenge window of the task is still open",
+            PocoError::DisputeAlreadyOpen => "Task is already disputed",
+            PocoError::NoOpenDispute => "Task has no open dispute",
+            PocoError::AlreadyVoted => "Verifier has already voted on the dispute",
+            PocoError::ConflictOfInterest => "Account is a party of the task",
+            PocoError::DisputeCommitteeTooSmall => "Task has too few verifiers to resolve a dispute",
+            PocoError::DisputeNotExpired => "Dispute has not expired yet",
+            PocoError::DisputeExpired => "Dispute has expired",
+        }
+    }
+
+    /// Encodes the error as a panic message, e.g. `POCO_ERROR:300: Task not found`.
+    pub fn encode(&self) -> String {
+        format!("{POCO_ERROR_PREFIX}{}: {}", self.code(), self.message())
+    }
+
+    /// Decodes the error from any text containing an encoded error,
+    /// such as the execution error of a failed transaction.
+    pub fn decode(text: &str) -> Option<Self> {
+        let (_, encoded) = text.split_once(POCO_ERROR_PREFIX)?;
+        let code = encoded.split(':').next()?;
+
+        code.parse().ok().and_then(Self::from_code)
+    }
+
+    /// Aborts the contract call with the encoded error.
+    pub fn panic(self) -> ! {
+        near_sdk::env::panic_str(&self.encode())
+    }
+
+    /// Aborts the contract call with the encoded error followed by `detail`.
+    pub fn panic_with(self, detail: impl Display) -> ! {
+        near_sdk::env::panic_str(&format!("{} ({detail})", self.encode()))
+    }
+}
+
+impl Display for PocoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (code {})", self.message(), self.code())
+    }
+}
+
+impl std::error::Error for PocoError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_and_decode() {
+        let error = PocoError::TaskNotFound;
+
+        assert_eq!(error.encode(), "POCO_ERROR:300: Task not found");
+        assert_eq!(PocoError::decode(&error.encode()), Some(error));
+        assert_eq!(
+            PocoError::decode("Smart contract panicked: POCO_ERROR:401: Stake is not enough"),
+            Some(PocoError::InsufficientStake)
+        );
+        assert_eq!(PocoError::decode("Smart contract panicked: Task not found"), None);
+        assert_eq!(PocoError::decode("POCO_ERROR:999: Unknown"), None);
+    }
+}
@@ -7,9 +7,15 @@ use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::AccountId;
 use near_sdk::schemars::JsonSchema;
 
-use crate::types::round::RoundId;
+use crate::types::dispute::DisputeStatus;
+use crate::types::role::Role;
+use crate::types::round::{RoundId, RoundSummary};
 use crate::types::task::id::TaskId;
-use crate::types::task::OnChainTaskConfig;
+use crate::types::task::status::{TaskAssignment, TaskResult, TaskStatus};
+use crate::types::task::task_type::TaskType;
+use crate::types::task::{OnChainTaskConfig, TaskOffer};
+use crate::types::uint::U256;
+use crate::types::user::Reputation;
 
 pub type EventNonce = u32;
 
@@ -32,6 +38,85 @@ impl Display for IndexedEvent {
     }
 }
 
+/// Result of an event query. Old events are dropped once the retention limit of the
+/// event bus is reached, so a query starting before `earliest_available` is reported as pruned.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "near_sdk::schemars")]
+#[serde(tag = "type")]
+#[serde(rename_all = "UPPERCASE")]
+pub enum EventQueryResult {
+    Ok { events: Vec<IndexedEvent> },
+    Pruned { earliest_available: EventNonce },
+}
+
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Copy, Debug, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "near_sdk::schemars")]
+#[serde(rename_all = "UPPERCASE")]
+pub enum StakeAction {
+    Stake,
+    Unstake,
+    Withdraw,
+    /// Stake taken from the account for proven misbehaviour, such as losing a dispute.
+    Slash,
+}
+
+/// Discriminant of `Events`, used to filter event queries by kind.
+#[derive(
+    BorshDeserialize,
+    BorshSerialize,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    strum::Display,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "near_sdk::schemars")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EventKind {
+    NewRoundEvent,
+    NewTaskEvent,
+    UserProfileFieldUpdateEvent,
+    TaskStatusUpdateEvent,
+    TaskResultSubmittedEvent,
+    StakeUpdateEvent,
+    RoleGrantedEvent,
+    RoleRevokedEvent,
+    RoundFinalizedEvent,
+    TaskCancelledEvent,
+    TaskOfferUpdatedEvent,
+    TaskTypeRegisteredEvent,
+    TaskTypeUnregisteredEvent,
+    TaskAssignedEvent,
+    WorkerFlaggedEvent,
+    DisputeOpenedEvent,
+    DisputeResolvedEvent,
+    ReputationUpdateEvent,
+}
+
+/// Criteria of a filtered event query, an event has to match all of the given ones.
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug, Default)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "near_sdk::schemars")]
+pub struct EventFilter {
+    pub kind: Option<EventKind>,
+    /// Matches events naming the account, as well as events of tasks it owns.
+    pub account: Option<AccountId>,
+    pub task_id: Option<TaskId>,
+    /// Matches events emitted while the round was the latest one.
+    pub round_id: Option<RoundId>,
+}
+
 #[near_bindgen(event_json(standard = "nep297"))]
 #[derive(BorshDeserialize, BorshSerialize, Deserialize, JsonSchema, Clone, Debug)]
 #[schemars(crate = "near_sdk::schemars")]
@@ -49,8 +134,83 @@ pub enum Events {
     UserProfileFieldUpdateEvent {
         user_id: AccountId,
         field: String,
+        /// New value of the field, empty once the field was removed.
         value: String,
     },
+
+    #[event_version("0.0.1")]
+    TaskStatusUpdateEvent { task_id: TaskId, status: TaskStatus },
+
+    #[event_version("0.0.1")]
+    TaskResultSubmittedEvent { task_id: TaskId, result: TaskResult },
+
+    #[event_version("0.0.1")]
+    StakeUpdateEvent {
+        user_id: AccountId,
+        action: StakeAction,
+        amount: U256,
+        stake: U256,
+    },
+
+    #[event_version("0.0.1")]
+    RoleGrantedEvent { user_id: AccountId, role: Role },
+
+    #[event_version("0.0.1")]
+    RoleRevokedEvent { user_id: AccountId, role: Role },
+
+    #[event_version("0.0.1")]
+    RoundFinalizedEvent { round_id: RoundId, summary: RoundSummary },
+
+    #[event_version("0.0.1")]
+    TaskCancelledEvent { task_id: TaskId },
+
+    #[event_version("0.0.1")]
+    TaskOfferUpdatedEvent {
+        task_id: TaskId,
+        offer: Vec<TaskOffer>,
+    },
+
+    #[event_version("0.0.1")]
+    TaskTypeRegisteredEvent { task_type: TaskType },
+
+    #[event_version("0.0.1")]
+    TaskTypeUnregisteredEvent { name: String },
+
+    #[event_version("0.0.1")]
+    TaskAssignedEvent {
+        task_id: TaskId,
+        assignment: TaskAssignment,
+    },
+
+    #[event_version("0.0.1")]
+    WorkerFlaggedEvent {
+        task_id: TaskId,
+        worker: AccountId,
+        digest: String,
+    },
+
+    #[event_version("0.0.1")]
+    DisputeOpenedEvent {
+        task_id: TaskId,
+        challenger: AccountId,
+        counter_digest: String,
+        bond: U256,
+    },
+
+    #[event_version("0.0.1")]
+    DisputeResolvedEvent {
+        task_id: TaskId,
+        status: DisputeStatus,
+        /// Digest the majority of verifiers reproduced, empty if the dispute expired.
+        digest: String,
+    },
+
+    #[event_version("0.0.1")]
+    ReputationUpdateEvent {
+        user_id: AccountId,
+        task_id: TaskId,
+        reputation: Reputation,
+    },
 }
 
 impl Events {
@@ -58,6 +218,73 @@ impl Events {
     pub fn log_event(&self) {
         self.emit()
     }
+
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Events::NewRoundEvent { .. } => EventKind::NewRoundEvent,
+            Events::NewTaskEvent { .. } => EventKind::NewTaskEvent,
+            Events::UserProfileFieldUpdateEvent { .. } => EventKind::UserProfileFieldUpdateEvent,
+            Events::TaskStatusUpdateEvent { .. } => EventKind::TaskStatusUpdateEvent,
+            Events::TaskResultSubmittedEvent { .. } => EventKind::TaskResultSubmittedEvent,
+            Events::StakeUpdateEvent { .. } => EventKind::StakeUpdateEvent,
+            Events::RoleGrantedEvent { .. } => EventKind::RoleGrantedEvent,
+            Events::RoleRevokedEvent { .. } => EventKind::RoleRevokedEvent,
+            Events::RoundFinalizedEvent { .. } => EventKind::RoundFinalizedEvent,
+            Events::TaskCancelledEvent { .. } => EventKind::TaskCancelledEvent,
+            Events::TaskOfferUpdatedEvent { .. } => EventKind::TaskOfferUpdatedEvent,
+            Events::TaskTypeRegisteredEvent { .. } => EventKind::TaskTypeRegisteredEvent,
+            Events::TaskTypeUnregisteredEvent { .. } => EventKind::TaskTypeUnregisteredEvent,
+            Events::TaskAssignedEvent { .. } => EventKind::TaskAssignedEvent,
+            Events::WorkerFlaggedEvent { .. } => EventKind::WorkerFlaggedEvent,
+            Events::DisputeOpenedEvent { .. } => EventKind::DisputeOpenedEvent,
+            Events::DisputeResolvedEvent { .. } => EventKind::DisputeResolvedEvent,
+            Events::ReputationUpdateEvent { .. } => EventKind::ReputationUpdateEvent,
+        }
+    }
+
+    /// Accounts named in the event itself.
+    pub fn accounts(&self) -> Vec<&AccountId> {
+        match self {
+            Events::NewTaskEvent { task_config, .. } => vec![&task_config.owner],
+            Events::UserProfileFieldUpdateEvent { user_id, .. }
+            | Events::StakeUpdateEvent { user_id, .. }
+            | Events::RoleGrantedEvent { user_id, .. }
+            | Events::RoleRevokedEvent { user_id, .. }
+            | Events::ReputationUpdateEvent { user_id, .. } => vec![user_id],
+            Events::WorkerFlaggedEvent { worker, .. } => vec![worker],
+            Events::DisputeOpenedEvent { challenger, .. } => vec![challenger],
+            Events::TaskResultSubmittedEvent { result, .. } => vec![&result.worker],
+            Events::TaskAssignedEvent { assignment, .. } => assignment
+                .workers
+                .iter()
+                .chain(assignment.verifiers.iter())
+                .collect(),
+            Events::NewRoundEvent { .. }
+            | Events::TaskStatusUpdateEvent { .. }
+            | Events::RoundFinalizedEvent { .. }
+            | Events::TaskCancelledEvent { .. }
+            | Events::TaskOfferUpdatedEvent { .. }
+            | Events::TaskTypeRegisteredEvent { .. }
+            | Events::TaskTypeUnregisteredEvent { .. }
+            | Events::DisputeResolvedEvent { .. } => vec![],
+        }
+    }
+
+    pub fn task_id(&self) -> Option<&TaskId> {
+        match self {
+            Events::NewTaskEvent { task_id, .. }
+            | Events::TaskStatusUpdateEvent { task_id, .. }
+            | Events::TaskResultSubmittedEvent { task_id, .. }
+            | Events::TaskCancelledEvent { task_id }
+            | Events::TaskOfferUpdatedEvent { task_id, .. }
+            | Events::TaskAssignedEvent { task_id, .. }
+            | Events::WorkerFlaggedEvent { task_id, .. }
+            | Events::DisputeOpenedEvent { task_id, .. }
+            | Events::DisputeResolvedEvent { task_id, .. }
+            | Events::ReputationUpdateEvent { task_id, .. } => Some(task_id),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(feature = "all")]
@@ -84,6 +311,97 @@ impl Display for Events {
                 "UserProfileFieldUpdateEvent {{ user_id: {}, field: {}, value: {} }}",
                 user_id, field, value
             ),
+            Events::TaskStatusUpdateEvent { task_id, status } => write!(
+                f,
+                "TaskStatusUpdateEvent {{ task_id: {}, status: {} }}",
+                task_id, status
+            ),
+            Events::TaskResultSubmittedEvent { task_id, result } => write!(
+                f,
+                "TaskResultSubmittedEvent {{ task_id: {}, worker: {}, output: {}, digest: {} }}",
+                task_id, result.worker, result.output, result.digest
+            ),
+            Events::StakeUpdateEvent {
+                user_id,
+                action,
+                amount,
+                stake,
+            } => write!(
+                f,
+                "StakeUpdateEvent {{ user_id: {}, action: {:?}, amount: {}, stake: {} }}",
+                user_id, action, amount, stake
+            ),
+            Events::RoleGrantedEvent { user_id, role } => {
+                write!(f, "RoleGrantedEvent {{ user_id: {user_id}, role: {role} }}")
+            }
+            Events::RoleRevokedEvent { user_id, role } => {
+                write!(f, "RoleRevokedEvent {{ user_id: {user_id}, role: {role} }}")
+            }
+            Events::RoundFinalizedEvent { round_id, summary } => write!(
+                f,
+                "RoundFinalizedEvent {{ round_id: {}, completed: {}, expired: {}, bounty_paid: {} }}",
+                round_id, summary.tasks_completed, summary.tasks_expired, summary.bounty_paid
+            ),
+            Events::TaskCancelledEvent { task_id } => {
+                write!(f, "TaskCancelledEvent {{ task_id: {task_id} }}")
+            }
+            Events::TaskOfferUpdatedEvent { task_id, offer } => write!(
+                f,
+                "TaskOfferUpdatedEvent {{ task_id: {}, offers: {} }}",
+                task_id,
+                offer.len()
+            ),
+            Events::TaskTypeRegisteredEvent { task_type } => write!(
+                f,
+                "TaskTypeRegisteredEvent {{ name: {}, version: {} }}",
+                task_type.name, task_type.version
+            ),
+            Events::TaskTypeUnregisteredEvent { name } => {
+                write!(f, "TaskTypeUnregisteredEvent {{ name: {name} }}")
+            }
+            Events::TaskAssignedEvent {
+                task_id,
+                assignment,
+            } => write!(
+                f,
+                "TaskAssignedEvent {{ task_id: {}, workers: {:?}, verifiers: {:?} }}",
+                task_id, assignment.workers, assignment.verifiers
+            ),
+            Events::WorkerFlaggedEvent {
+                task_id,
+                worker,
+                digest,
+            } => write!(
+                f,
+                "WorkerFlaggedEvent {{ task_id: {task_id}, worker: {worker}, digest: {digest} }}"
+            ),
+            Events::DisputeOpenedEvent {
+                task_id,
+                challenger,
+                counter_digest,
+                bond,
+            } => write!(
+                f,
+                "DisputeOpenedEvent {{ task_id: {}, challenger: {}, counter_digest: {}, bond: {} }}",
+                task_id, challenger, counter_digest, bond
+            ),
+            Events::DisputeResolvedEvent {
+                task_id,
+                status,
+                digest,
+            } => write!(
+                f,
+                "DisputeResolvedEvent {{ task_id: {task_id}, status: {status}, digest: {digest} }}"
+            ),
+            Events::ReputationUpdateEvent {
+                user_id,
+                task_id,
+                reputation,
+            } => write!(
+                f,
+                "ReputationUpdateEvent {{ user_id: {}, task_id: {}, completed: {}, failed: {}, score: {} }}",
+                user_id, task_id, reputation.completed, reputation.failed, reputation.score
+            ),
         }
     }
 }
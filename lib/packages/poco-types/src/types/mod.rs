@@ -1,4 +1,8 @@
+pub mod config;
+pub mod dispute;
+pub mod error;
 pub mod event;
+pub mod role;
 pub mod round;
 pub mod task;
 pub mod uint;
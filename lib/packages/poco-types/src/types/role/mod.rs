@@ -0,0 +1,31 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::schemars::JsonSchema;
+use strum::Display;
+
+#[derive(
+    BorshDeserialize,
+    BorshSerialize,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Debug,
+    PartialEq,
+    Eq,
+    Copy,
+    Clone,
+    Display,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[borsh(crate = "near_sdk::borsh")]
+#[schemars(crate = "near_sdk::schemars")]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Role {
+    /// Manages roles and protocol parameters, and passes every other role check.
+    Owner,
+    RoundKeeper,
+    Verifier,
+    Pauser,
+    /// Manages the task type registry.
+    Governance,
+}
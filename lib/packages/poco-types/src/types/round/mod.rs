@@ -8,6 +8,7 @@ use std::fmt::{Display, Formatter};
 use std::ops::{Add, Sub};
 use strum::Display;
 use crate::types::event::EventNonce;
+use crate::types::uint::U256;
 
 pub type RoundId = u32;
 
@@ -108,6 +109,12 @@ impl From<u64> for RoundDuration {
     }
 }
 
+/// Status of a round.
+///
+/// Breaking change: an ended round used to report `Pending`. It now reports `Finalizing`
+/// until `finalize_round` has run for it and `Finalized` afterwards, and `Pending` is only
+/// reported before the first round has started. Clients that took `Pending` to mean the
+/// round has ended should check for anything but `Running` instead.
 #[derive(
     BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Debug, PartialEq, Display,
 )]
@@ -116,7 +123,25 @@ impl From<u64> for RoundDuration {
 #[serde(rename_all = "UPPERCASE")]
 pub enum RoundStatus {
     Running,
+    /// No round has been started yet.
     Pending,
+    /// The round has ended and is waiting for `finalize_round`.
+    Finalizing,
+    /// The round has ended and its tasks have been settled or expired.
+    Finalized,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+#[borsh(crate = "near_sdk::borsh")]
+#[schemars(crate = "near_sdk::schemars")]
+pub struct RoundSummary {
+    pub round_id: RoundId,
+    pub tasks_published: u32,
+    pub tasks_completed: u32,
+    pub tasks_expired: u32,
+    pub bounty_paid: U256,
+    pub finalized_at: BlockTimestamp,
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq)]
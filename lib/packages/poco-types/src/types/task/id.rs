@@ -11,7 +11,7 @@ use crate::types::round::RoundId;
 use crate::types::task::TaskNonce;
 
 #[derive(
-    BorshDeserialize, BorshSerialize, JsonSchema, PartialEq, PartialOrd, Hash, Clone, Debug,
+    BorshDeserialize, BorshSerialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Debug,
 )]
 pub struct TaskId(RoundId, TaskNonce);
 
@@ -6,13 +6,73 @@ use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::AccountId;
 use schemars::JsonSchema;
 
+use crate::types::error::PocoError;
+use crate::types::round::{BlockTimestamp, RoundDuration};
 use crate::types::task::id::TaskId;
 use crate::types::uint::U256;
 
 pub mod id;
+pub mod status;
+pub mod task_type;
 
 pub type TaskNonce = u32;
 
+/// Upper bound of `TaskConfig::replicas`, every replica is a result the contract compares.
+pub const MAX_REPLICAS: u32 = 16;
+
+/// Upper bound of the length in bytes of a submitted output, enough for any CID or a long URL.
+pub const MAX_OUTPUT_BYTES: usize = 512;
+
+/// Upper bound of the length in bytes of a submitted digest, enough for a hex SHA-512.
+pub const MAX_DIGEST_BYTES: usize = 128;
+
+/// URL schemes a `Link` input or output may use.
+pub const ALLOWED_LINK_SCHEMES: &[&str] = &["http", "https"];
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz234567";
+
+/// Checks that `cid` is syntactically a CIDv0 (base58 `Qm...`) or a base32 CIDv1 (`b...`).
+/// The multihash itself is not decoded.
+pub fn is_valid_cid(cid: &str) -> bool {
+    if let Some(rest) = cid.strip_prefix("Qm") {
+        cid.len() == 46 && rest.chars().all(|c| BASE58_ALPHABET.contains(c))
+    } else if let Some(rest) = cid.strip_prefix('b') {
+        rest.len() >= 8 && rest.chars().all(|c| BASE32_ALPHABET.contains(c))
+    } else {
+        false
+    }
+}
+
+fn default_replicas() -> u32 {
+    1
+}
+
+/// Escrow a task needs to pay the largest bounty to each of its replicas.
+pub fn required_escrow(offers: &[TaskOffer], replicas: u32) -> U256 {
+    let max_bounty = offers
+        .iter()
+        .map(|offer| offer.bounty)
+        .max()
+        .unwrap_or_default();
+
+    max_bounty.saturating_mul(U256::from(replicas.max(1)))
+}
+
+fn validate_link(url: &str) -> Result<(), PocoError> {
+    match url.split_once("://") {
+        Some((scheme, rest))
+            if !rest.is_empty()
+                && ALLOWED_LINK_SCHEMES
+                    .iter()
+                    .any(|allowed| scheme.eq_ignore_ascii_case(allowed)) =>
+        {
+            Ok(())
+        }
+        _ => Err(PocoError::UnsupportedLinkScheme),
+    }
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
 #[serde(crate = "near_sdk::serde")]
 #[serde(tag = "type")]
@@ -69,6 +129,10 @@ pub struct OnChainTaskConfig {
     pub offer: Vec<TaskOffer>,
     pub config: Vec<u8>,
     pub r#type: String,
+    pub claim_deadline: Option<BlockTimestamp>,
+    pub execution_timeout: Option<RoundDuration>,
+    pub replicas: u32,
+    pub quorum: u32,
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
@@ -80,15 +144,90 @@ pub struct TaskConfig {
     pub offer: Vec<TaskOffer>,
     pub config: Vec<u8>,
     pub r#type: String,
+    /// The task can no longer be claimed after this time.
+    #[serde(default)]
+    pub claim_deadline: Option<BlockTimestamp>,
+    /// Time a worker has from claiming the task to submitting its result,
+    /// after which the claim is released and the task is published again.
+    #[serde(default)]
+    pub execution_timeout: Option<RoundDuration>,
+    /// Number of workers executing the task independently.
+    #[serde(default = "default_replicas")]
+    pub replicas: u32,
+    /// Number of replicas whose output digests have to agree for the task to be verified.
+    #[serde(default = "default_replicas")]
+    pub quorum: u32,
+}
+
+impl TaskInputSource {
+    pub fn validate(&self) -> Result<(), PocoError> {
+        match self {
+            TaskInputSource::Ipfs { hash } if !is_valid_cid(hash) => Err(PocoError::InvalidCid),
+            TaskInputSource::Ipfs { .. } => Ok(()),
+            TaskInputSource::Link { url } => validate_link(url),
+        }
+    }
+}
+
+impl TaskOutputSource {
+    pub fn validate(&self) -> Result<(), PocoError> {
+        match self {
+            TaskOutputSource::Ipfs => Ok(()),
+            TaskOutputSource::Link { url } => validate_link(url),
+        }
+    }
+
+    /// Checks a submitted result: `output` has to reference the output the way this source
+    /// does, a CID for IPFS and a link for links, and neither it nor `digest` may exceed
+    /// their size limit.
+    pub fn validate_result(&self, output: &str, digest: &str) -> Result<(), PocoError> {
+        if output.len() > MAX_OUTPUT_BYTES || digest.len() > MAX_DIGEST_BYTES {
+            return Err(PocoError::TaskResultTooLarge);
+        }
+
+        match self {
+            TaskOutputSource::Ipfs if !is_valid_cid(output) => Err(PocoError::InvalidCid),
+            TaskOutputSource::Ipfs => Ok(()),
+            TaskOutputSource::Link { .. } => validate_link(output),
+        }
+    }
 }
 
 impl TaskConfig {
-    pub fn to_on_chain_task_config(
-        self,
-        owner: AccountId,
-        id: TaskId,
-    ) -> anyhow::Result<OnChainTaskConfig> {
-        Ok(OnChainTaskConfig {
+    /// Checks everything about the config that does not depend on contract state,
+    /// except for the task type, which has to be looked up in the task type registry.
+    pub fn validate(&self, max_config_bytes: u32) -> Result<(), PocoError> {
+        self.input.validate()?;
+        self.output.validate()?;
+
+        if self.offer.is_empty() {
+            return Err(PocoError::MissingOffer);
+        }
+
+        if self.config.len() > max_config_bytes as usize {
+            return Err(PocoError::TaskConfigTooLarge);
+        }
+
+        if !(1..=MAX_REPLICAS).contains(&self.replicas) || !(1..=self.replicas).contains(&self.quorum) {
+            return Err(PocoError::InvalidReplication);
+        }
+
+        // A claim that times out as soon as it is made could never be worked on.
+        if self.execution_timeout == Some(RoundDuration::new(0)) {
+            return Err(PocoError::InvalidTaskConfig);
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn required_escrow(&self) -> U256 {
+        required_escrow(&self.offer, self.replicas)
+    }
+
+    /// Only converts, `validate` is expected to have been run on the config before.
+    pub fn to_on_chain_task_config(self, owner: AccountId, id: TaskId) -> OnChainTaskConfig {
+        OnChainTaskConfig {
             owner,
             id,
             input: self.input,
@@ -97,19 +236,42 @@ impl TaskConfig {
             offer: self.offer,
             config: self.config,
             r#type: self.r#type,
-        })
+            claim_deadline: self.claim_deadline,
+            execution_timeout: self.execution_timeout,
+            replicas: self.replicas,
+            quorum: self.quorum,
+        }
+    }
+}
+
+impl OnChainTaskConfig {
+    pub fn to_task_config(self) -> TaskConfig {
+        TaskConfig {
+            input: self.input,
+            output: self.output,
+            requirements: self.requirements,
+            offer: self.offer,
+            config: self.config,
+            r#type: self.r#type,
+            claim_deadline: self.claim_deadline,
+            execution_timeout: self.execution_timeout,
+            replicas: self.replicas,
+            quorum: self.quorum,
+        }
     }
 }
 
 impl TaskRequirement {
-    pub fn is_ok(&self, rhs: &U256) -> bool {
+    /// Checks a user's `property` value against this requirement,
+    /// e.g. `stake GREATER_THAN 0x100` holds when `value > 0x100`.
+    pub fn is_ok(&self, value: &U256) -> bool {
         match self.operator {
-            TaskRequirementOperator::Equal => self.value == *rhs,
-            TaskRequirementOperator::NotEqual => self.value != *rhs,
-            TaskRequirementOperator::GreaterThan => self.value > *rhs,
-            TaskRequirementOperator::GreaterThanOrEqual => self.value >= *rhs,
-            TaskRequirementOperator::LessThan => self.value < *rhs,
-            TaskRequirementOperator::LessThanOrEqual => self.value <= *rhs,
+            TaskRequirementOperator::Equal => *value == self.value,
+            TaskRequirementOperator::NotEqual => *value != self.value,
+            TaskRequirementOperator::GreaterThan => *value > self.value,
+            TaskRequirementOperator::GreaterThanOrEqual => *value >= self.value,
+            TaskRequirementOperator::LessThan => *value < self.value,
+            TaskRequirementOperator::LessThanOrEqual => *value <= self.value,
         }
     }
 }
@@ -119,8 +281,158 @@ impl Display for OnChainTaskConfig {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "TaskConfig {{ owner: {}, id: {}, input: {:?}, output: {:?}, requirements: {:?}, offer: {:?}, config: {:?}, type: {:?} }}",
-            self.owner, self.id, self.input, self.output, self.requirements, self.offer, self.config, self.r#type
+            "TaskConfig {{ owner: {}, id: {}, input: {:?}, output: {:?}, requirements: {:?}, offer: {:?}, config: {:?}, type: {:?}, claim_deadline: {:?}, execution_timeout: {:?}, replicas: {}, quorum: {} }}",
+            self.owner,
+            self.id,
+            self.input,
+            self.output,
+            self.requirements,
+            self.offer,
+            self.config,
+            self.r#type,
+            self.claim_deadline,
+            self.execution_timeout,
+            self.replicas,
+            self.quorum
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_requirement_is_ok() {
+        let requirement = TaskRequirement {
+            property: "stake".to_string(),
+            operator: TaskRequirementOperator::GreaterThan,
+            value: U256::from(0x100),
+        };
+
+        assert!(requirement.is_ok(&U256::from(0x101)));
+        assert!(!requirement.is_ok(&U256::from(0x100)));
+        assert!(!requirement.is_ok(&U256::from(0x0ff)));
+    }
+
+    #[test]
+    fn test_task_config_validate() {
+        let config = TaskConfig {
+            input: TaskInputSource::Ipfs {
+                hash: "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+            },
+            output: TaskOutputSource::Link {
+                url: "https://example.com/output".to_string(),
+            },
+            requirements: vec![],
+            offer: vec![TaskOffer {
+                bounty: U256::from(0x100),
+                requirements: None,
+            }],
+            config: vec![0; 16],
+            r#type: "MEDIA_TRANSCODING".to_string(),
+            claim_deadline: None,
+            execution_timeout: None,
+            replicas: 3,
+            quorum: 2,
+        };
+
+        assert_eq!(config.validate(16), Ok(()));
+        assert_eq!(config.required_escrow(), U256::from(0x300));
+        assert_eq!(config.validate(15), Err(PocoError::TaskConfigTooLarge));
+
+        let invalid_cid = TaskConfig {
+            input: TaskInputSource::Ipfs {
+                hash: "Qm0000".to_string(),
+            },
+            ..config.clone()
+        };
+        assert_eq!(invalid_cid.validate(16), Err(PocoError::InvalidCid));
+
+        let invalid_scheme = TaskConfig {
+            output: TaskOutputSource::Link {
+                url: "ftp://example.com/output".to_string(),
+            },
+            ..config.clone()
+        };
+        assert_eq!(invalid_scheme.validate(16), Err(PocoError::UnsupportedLinkScheme));
+
+        let invalid_quorum = TaskConfig {
+            quorum: 4,
+            ..config.clone()
+        };
+        assert_eq!(invalid_quorum.validate(16), Err(PocoError::InvalidReplication));
+
+        let missing_offer = TaskConfig {
+            offer: vec![],
+            ..config.clone()
+        };
+        assert_eq!(missing_offer.validate(16), Err(PocoError::MissingOffer));
+
+        let zero_timeout = TaskConfig {
+            execution_timeout: Some(RoundDuration::new(0)),
+            ..config
+        };
+        assert_eq!(zero_timeout.validate(16), Err(PocoError::InvalidTaskConfig));
+    }
+
+    #[cfg(feature = "all")]
+    #[test]
+    fn test_on_chain_task_config_display() {
+        let config = OnChainTaskConfig {
+            owner: "alice.near".parse().unwrap(),
+            id: TaskId::new(1, 0),
+            input: TaskInputSource::Ipfs {
+                hash: "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK".to_string(),
+            },
+            output: TaskOutputSource::Ipfs,
+            requirements: vec![],
+            offer: vec![],
+            config: vec![],
+            r#type: "MEDIA_TRANSCODING".to_string(),
+            claim_deadline: None,
+            execution_timeout: Some(RoundDuration::new(1_000)),
+            replicas: 3,
+            quorum: 2,
+        };
+        let text = config.to_string();
+
+        assert!(text.contains("claim_deadline: None"));
+        assert!(text.contains("execution_timeout: Some("));
+        assert!(text.contains("replicas: 3, quorum: 2"));
+    }
+
+    #[test]
+    fn test_is_valid_cid() {
+        assert!(is_valid_cid("QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK"));
+        assert!(is_valid_cid(
+            "bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+        ));
+        assert!(!is_valid_cid("QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZL0K"));
+        assert!(!is_valid_cid("https://example.com"));
+        assert!(!is_valid_cid(""));
+    }
+
+    #[test]
+    fn test_validate_result() {
+        let cid = "QmepT6gr3qg2qfxuxhrh37puyT4Mg7dzRm8yZXkXTqZLoK";
+        let link = TaskOutputSource::Link {
+            url: "https://example.com/upload".to_string(),
+        };
+
+        assert_eq!(TaskOutputSource::Ipfs.validate_result(cid, "digest"), Ok(()));
+        assert_eq!(
+            TaskOutputSource::Ipfs.validate_result("https://example.com/out", "digest"),
+            Err(PocoError::InvalidCid)
+        );
+        assert_eq!(
+            TaskOutputSource::Ipfs.validate_result(cid, &"0".repeat(MAX_DIGEST_BYTES + 1)),
+            Err(PocoError::TaskResultTooLarge)
+        );
+        assert_eq!(link.validate_result("https://example.com/out", "digest"), Ok(()));
+        assert_eq!(
+            link.validate_result("ftp://example.com/out", "digest"),
+            Err(PocoError::UnsupportedLinkScheme)
+        );
+    }
+}
@@ -0,0 +1,183 @@
+#[cfg(feature = "all")]
+use std::fmt::{Display, Formatter};
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+use schemars::JsonSchema;
+
+use crate::types::round::BlockTimestamp;
+use crate::types::uint::U256;
+
+#[derive(
+    BorshDeserialize,
+    BorshSerialize,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Debug,
+    PartialEq,
+    Eq,
+    Copy,
+    Clone,
+    strum::Display,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TaskStatus {
+    Published,
+    Claimed,
+    Submitted,
+    Verified,
+    Rejected,
+    Settled,
+    Expired,
+    Cancelled,
+    Disputed,
+}
+
+impl TaskStatus {
+    /// Whether the lifecycle allows moving from `self` to `next`.
+    ///
+    /// Published -> Claimed -> Submitted -> Verified/Rejected -> Settled,
+    /// a claim that timed out may be published again, and any task that has not been
+    /// submitted yet, or was rejected, may expire. Only published tasks can be cancelled.
+    /// A submitted or verified result may be disputed, which either restores the task or
+    /// rejects it.
+    pub fn can_transit_to(&self, next: TaskStatus) -> bool {
+        use TaskStatus::*;
+
+        matches!(
+            (self, next),
+            (Published, Claimed)
+                | (Published, Expired)
+                | (Published, Cancelled)
+                | (Claimed, Submitted)
+                | (Claimed, Published)
+                | (Claimed, Expired)
+                | (Submitted, Verified)
+                | (Submitted, Rejected)
+                | (Verified, Settled)
+                | (Rejected, Expired)
+                | (Submitted, Disputed)
+                | (Verified, Disputed)
+                | (Disputed, Submitted)
+                | (Disputed, Verified)
+                | (Disputed, Rejected)
+        )
+    }
+
+    #[inline]
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            TaskStatus::Settled | TaskStatus::Expired | TaskStatus::Cancelled
+        )
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TaskAttempt {
+    pub worker: AccountId,
+    pub claimed_at: BlockTimestamp,
+    /// Index of the offer in `OnChainTaskConfig::offer` the worker qualified for.
+    pub offer_index: Option<u32>,
+    pub result: Option<TaskResult>,
+}
+
+/// Workers and verifiers drawn for a task when it was published, weighted by their stake.
+/// The draw can be replayed from `seed` and the stakes at the block of `assigned_at`.
+/// Without any eligible candidate the list stays empty and anyone eligible may take part.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TaskAssignment {
+    pub workers: Vec<AccountId>,
+    pub verifiers: Vec<AccountId>,
+    pub seed: [u8; 32],
+    pub assigned_at: BlockTimestamp,
+}
+
+/// A result reported by a worker. `output` is a reference matching the task's
+/// `TaskOutputSource`, e.g. an IPFS CID or a link, and `digest` is the hash of the output.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TaskResult {
+    pub worker: AccountId,
+    pub submitted_at: BlockTimestamp,
+    pub output: String,
+    pub digest: String,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TaskState {
+    pub status: TaskStatus,
+    pub updated_at: BlockTimestamp,
+    pub attempts: Vec<TaskAttempt>,
+    /// Bounty paid out to workers when the task was settled.
+    pub bounty_paid: U256,
+    /// Output digest a quorum of replicas agreed on.
+    pub agreed_digest: Option<String>,
+    /// Workers whose digest disagreed with the quorum.
+    pub flagged: Vec<AccountId>,
+}
+
+impl TaskState {
+    pub fn new(published_at: BlockTimestamp) -> Self {
+        TaskState {
+            status: TaskStatus::Published,
+            updated_at: published_at,
+            attempts: Vec::new(),
+            bounty_paid: U256::zero(),
+            agreed_digest: None,
+            flagged: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn current_attempt(&self) -> Option<&TaskAttempt> {
+        self.attempts.last()
+    }
+
+    #[inline]
+    pub fn results(&self) -> impl Iterator<Item = &TaskResult> {
+        self.attempts.iter().filter_map(|attempt| attempt.result.as_ref())
+    }
+}
+
+#[cfg(feature = "all")]
+impl Display for TaskState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TaskState {{ status: {}, attempts: {} }}",
+            self.status,
+            self.attempts.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_status_transitions() {
+        assert!(TaskStatus::Published.can_transit_to(TaskStatus::Claimed));
+        assert!(TaskStatus::Claimed.can_transit_to(TaskStatus::Submitted));
+        assert!(TaskStatus::Submitted.can_transit_to(TaskStatus::Verified));
+        assert!(TaskStatus::Verified.can_transit_to(TaskStatus::Settled));
+        assert!(TaskStatus::Claimed.can_transit_to(TaskStatus::Published));
+        assert!(TaskStatus::Rejected.can_transit_to(TaskStatus::Expired));
+
+        assert!(!TaskStatus::Published.can_transit_to(TaskStatus::Submitted));
+        assert!(!TaskStatus::Claimed.can_transit_to(TaskStatus::Cancelled));
+        assert!(!TaskStatus::Submitted.can_transit_to(TaskStatus::Expired));
+        assert!(!TaskStatus::Settled.can_transit_to(TaskStatus::Published));
+        assert!(!TaskStatus::Rejected.can_transit_to(TaskStatus::Published));
+        assert!(TaskStatus::Verified.can_transit_to(TaskStatus::Disputed));
+        assert!(TaskStatus::Disputed.can_transit_to(TaskStatus::Rejected));
+        assert!(!TaskStatus::Disputed.can_transit_to(TaskStatus::Settled));
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(feature = "all")]
+use std::fmt::{Display, Formatter};
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+use crate::types::error::PocoError;
+use crate::types::task::is_valid_cid;
+
+/// An entry of the on-chain task type registry, e.g. `MEDIA_TRANSCODING`.
+/// `TaskConfig::config` of a task of this type has to match the JSON Schema stored
+/// on IPFS under `schema_cid`, so publishers and workers agree on how to read it.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TaskType {
+    pub name: String,
+    /// Raised whenever the schema changes.
+    pub version: u32,
+    pub description: String,
+    pub schema_cid: String,
+}
+
+impl TaskType {
+    pub fn validate(&self) -> Result<(), PocoError> {
+        if !is_valid_cid(&self.schema_cid) {
+            return Err(PocoError::InvalidCid);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "all")]
+impl Display for TaskType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TaskType {{ name: {}, version: {}, schema_cid: {}, description: {} }}",
+            self.name, self.version, self.schema_cid, self.description
+        )
+    }
+}
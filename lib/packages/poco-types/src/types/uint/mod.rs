@@ -1,3 +1,6 @@
+// Lints on the code `construct_uint!` expands to.
+#![allow(clippy::assign_op_pattern, clippy::manual_div_ceil)]
+
 use impl_serde::impl_uint_serde;
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::schemars::JsonSchema;
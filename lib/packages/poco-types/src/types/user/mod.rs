@@ -1,15 +1,156 @@
+// `store::UnorderedMap` is deprecated in near-sdk 5 without a replacement yet.
+#![allow(deprecated)]
+
+use std::collections::BTreeMap;
+
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::serde::Serialize;
-use near_sdk::store::{LazyOption, UnorderedMap};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::store::UnorderedMap;
 use near_sdk::AccountId;
 use near_sdk::schemars::JsonSchema;
 
+use crate::types::error::PocoError;
+use crate::types::round::RoundId;
 use crate::types::uint::U256;
 
+/// Built-in property resolving to the account's staked balance in yoctoNEAR.
+pub const STAKE_PROPERTY: &str = "stake";
+/// Built-in property resolving to the account's rolling reputation score.
+pub const REPUTATION_PROPERTY: &str = "reputation";
+/// Properties resolved by the contract, users cannot declare them.
+pub const RESERVED_PROPERTIES: &[&str] = &[STAKE_PROPERTY, REPUTATION_PROPERTY];
+pub const MAX_PROPERTY_NAME_LEN: usize = 32;
+/// Most properties a user can declare on its profile.
+pub const MAX_USER_PROPERTIES: u32 = 32;
+
+pub const MAX_REPUTATION_SCORE: u32 = 10_000;
+/// Score of an account without any recorded outcome.
+pub const INITIAL_REPUTATION_SCORE: u32 = MAX_REPUTATION_SCORE / 2;
+/// Number of recent outcomes the rolling score roughly averages over.
+pub const REPUTATION_WINDOW: u32 = 20;
+
+pub const MAX_ENDPOINT_LEN: usize = 256;
+
+/// Protocol of an endpoint a user exposes, each user has at most one endpoint per protocol.
+#[derive(
+    BorshDeserialize,
+    BorshSerialize,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    strum::Display,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "near_sdk::schemars")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum EndpointProtocol {
+    /// Transcoder port, e.g. `grpc://worker.example.com:50051`.
+    Grpc,
+    /// Control API, e.g. `https://worker.example.com/api`.
+    Http,
+    /// Peer multiaddr, e.g. `/dns4/worker.example.com/tcp/4001/p2p/12D3KooW...`.
+    Ipfs,
+}
+
+impl EndpointProtocol {
+    fn address_prefixes(&self) -> &'static [&'static str] {
+        match self {
+            EndpointProtocol::Grpc => &["grpc://", "grpcs://", "http://", "https://"],
+            EndpointProtocol::Http => &["http://", "https://"],
+            EndpointProtocol::Ipfs => &["/ip4/", "/ip6/", "/dns/", "/dns4/", "/dns6/"],
+        }
+    }
+
+    /// Checks that `url` is an address of this protocol.
+    pub fn validate(&self, url: &str) -> Result<(), PocoError> {
+        let has_address = self
+            .address_prefixes()
+            .iter()
+            .any(|prefix| url.strip_prefix(prefix).is_some_and(|rest| !rest.is_empty()));
+        let is_valid = has_address
+            && url.len() <= MAX_ENDPOINT_LEN
+            && !url.chars().any(|c| c.is_whitespace() || c.is_control());
+
+        if is_valid {
+            Ok(())
+        } else {
+            Err(PocoError::InvalidEndpoint)
+        }
+    }
+}
+
+/// Checks that a user may declare a property with this name: lowercase ASCII letters,
+/// digits and underscores, such as `cpu_cores`, and not one of `RESERVED_PROPERTIES`.
+pub fn validate_property_name(name: &str) -> Result<(), PocoError> {
+    if RESERVED_PROPERTIES.contains(&name) {
+        return Err(PocoError::ReservedProperty);
+    }
+
+    let is_valid = !name.is_empty()
+        && name.len() <= MAX_PROPERTY_NAME_LEN
+        && name
+            .bytes()
+            .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'_');
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(PocoError::InvalidPropertyName)
+    }
+}
+
+/// Track record of an account, updated by the contract whenever a task it worked on
+/// is verified, rejected, expired or disputed.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "near_sdk::schemars")]
+pub struct Reputation {
+    pub completed: u32,
+    pub failed: u32,
+    /// Exponential moving average of the outcomes, from 0 to `MAX_REPUTATION_SCORE`.
+    pub score: u32,
+}
+
+impl Reputation {
+    /// Counts the outcome and moves the score a `REPUTATION_WINDOW`th of the way
+    /// towards `MAX_REPUTATION_SCORE` on success or 0 on failure.
+    pub fn record(&mut self, success: bool) {
+        let target = if success {
+            self.completed = self.completed.saturating_add(1);
+            MAX_REPUTATION_SCORE
+        } else {
+            self.failed = self.failed.saturating_add(1);
+            0
+        };
+
+        self.score = (self.score * (REPUTATION_WINDOW - 1) + target) / REPUTATION_WINDOW;
+    }
+}
+
+impl Default for Reputation {
+    fn default() -> Self {
+        Reputation {
+            completed: 0,
+            failed: 0,
+            score: INITIAL_REPUTATION_SCORE,
+        }
+    }
+}
+
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct InternalUserProfile {
+    /// Properties declared by the user, the built-in ones are resolved by the contract.
     props: UnorderedMap<String, U256>,
-    endpoint: LazyOption<String>,
+    endpoints: UnorderedMap<EndpointProtocol, String>,
+    reputation: Reputation,
 }
 
 #[derive(Serialize, JsonSchema)]
@@ -25,12 +166,19 @@ pub struct UserProperty<'a> {
 #[serde(crate = "near_sdk::serde")]
 pub struct UserProfile<'a> {
     props: Vec<UserProperty<'a>>,
-    endpoint: &'a Option<String>,
+    endpoints: BTreeMap<EndpointProtocol, &'a str>,
+    reputation: &'a Reputation,
+}
+
+/// Stake waiting out the unbonding period before it can be withdrawn.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, JsonSchema, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[schemars(crate = "near_sdk::schemars")]
+pub struct UnbondingStake {
+    pub amount: U256,
+    pub unlock_round: RoundId,
 }
 
-// 问题：在这个Props可能用来放什么东西呢？
-// 1. hash签名
-// 2. 余额
 impl InternalUserProfile {
     #[inline]
     pub fn new(account: &AccountId) -> Self {
@@ -39,18 +187,44 @@ impl InternalUserProfile {
 
         InternalUserProfile {
             props,
-            endpoint: LazyOption::new(format!("{prefix}:endpoint").as_bytes().to_vec(), None),
+            endpoints: UnorderedMap::new(format!("{prefix}:endpoints").as_bytes().to_vec()),
+            reputation: Reputation::default(),
         }
     }
 
     #[inline]
-    pub fn get_endpoint(&self) -> &Option<String> {
-        self.endpoint.get()
+    pub fn get_endpoint(&self, protocol: EndpointProtocol) -> Option<&String> {
+        self.endpoints.get(&protocol)
+    }
+
+    #[inline]
+    pub fn get_endpoints(&self) -> BTreeMap<EndpointProtocol, &str> {
+        self.endpoints
+            .iter()
+            .map(|(protocol, url)| (*protocol, url.as_str()))
+            .collect()
+    }
+
+    #[inline]
+    pub fn set_endpoint(&mut self, protocol: EndpointProtocol, url: String) {
+        self.endpoints.insert(protocol, url);
+    }
+
+    #[inline]
+    pub fn remove_endpoint(&mut self, protocol: EndpointProtocol) -> Option<String> {
+        self.endpoints.remove(&protocol)
+    }
+
+    #[inline]
+    pub fn get_reputation(&self) -> &Reputation {
+        &self.reputation
     }
 
     #[inline]
-    pub fn set_endpoint(&mut self, endpoint: String) {
-        self.endpoint.replace(endpoint);
+    pub fn record_outcome(&mut self, success: bool) -> &Reputation {
+        self.reputation.record(success);
+
+        &self.reputation
     }
 
     #[inline]
@@ -62,6 +236,16 @@ impl InternalUserProfile {
     pub fn set_prop(&mut self, name: &str, value: &U256) {
         self.props.insert(name.to_string(), *value);
     }
+
+    #[inline]
+    pub fn remove_prop(&mut self, name: &str) -> Option<U256> {
+        self.props.remove(name)
+    }
+
+    #[inline]
+    pub fn prop_count(&self) -> u32 {
+        self.props.len()
+    }
 }
 
 impl<'a, 'b: 'a> From<&'b InternalUserProfile> for UserProfile<'a> {
@@ -72,7 +256,54 @@ impl<'a, 'b: 'a> From<&'b InternalUserProfile> for UserProfile<'a> {
                 .iter()
                 .map(|(key, value)| UserProperty { key, value })
                 .collect(),
-            endpoint: profile.endpoint.get(),
+            endpoints: profile.get_endpoints(),
+            reputation: &profile.reputation,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reputation_record() {
+        let mut reputation = Reputation::default();
+
+        reputation.record(true);
+        assert_eq!(reputation.completed, 1);
+        assert_eq!(reputation.score, 5_250);
+
+        reputation.record(false);
+        assert_eq!(reputation.failed, 1);
+        assert_eq!(reputation.score, 4_987);
+    }
+
+    #[test]
+    fn test_validate_endpoint() {
+        assert_eq!(EndpointProtocol::Grpc.validate("grpc://worker.example.com:50051"), Ok(()));
+        assert_eq!(EndpointProtocol::Http.validate("https://worker.example.com/api"), Ok(()));
+        assert_eq!(
+            EndpointProtocol::Ipfs.validate("/ip4/10.0.0.1/tcp/4001/p2p/12D3KooWExample"),
+            Ok(())
+        );
+        assert_eq!(
+            EndpointProtocol::Http.validate("grpc://worker.example.com:50051"),
+            Err(PocoError::InvalidEndpoint)
+        );
+        assert_eq!(EndpointProtocol::Http.validate("https://"), Err(PocoError::InvalidEndpoint));
+        assert_eq!(
+            EndpointProtocol::Ipfs.validate("/ip4/10.0.0.1 /tcp/4001"),
+            Err(PocoError::InvalidEndpoint)
+        );
+    }
+
+    #[test]
+    fn test_validate_property_name() {
+        assert_eq!(validate_property_name("h265_fps"), Ok(()));
+        assert_eq!(validate_property_name("stake"), Err(PocoError::ReservedProperty));
+        assert_eq!(validate_property_name("reputation"), Err(PocoError::ReservedProperty));
+        assert_eq!(validate_property_name(""), Err(PocoError::InvalidPropertyName));
+        assert_eq!(validate_property_name("CPU cores"), Err(PocoError::InvalidPropertyName));
+    }
+}